@@ -4,4 +4,5 @@ pub mod leap_year;
 pub mod mars_rover;
 pub mod mars_rover2;
 pub mod roman_numerals;
+pub mod rover;
 pub mod tic_tac_toe;
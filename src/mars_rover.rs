@@ -1,7 +1,123 @@
+use std::collections::HashSet;
 use std::fmt;
 use std::str::FromStr;
 
-enum Direction {
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EdgePolicy {
+    Stop,
+    Reject,
+    Lost,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RoverParseError {
+    MissingLine {
+        line: usize,
+        expected: &'static str,
+    },
+    MissingToken {
+        line: usize,
+        column: usize,
+        expected: &'static str,
+    },
+    UnexpectedToken {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    InvalidCoordinate {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    InvalidDirection {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    InvalidCommand {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    OutsidePlateau {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+}
+
+impl fmt::Display for RoverParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoverParseError::MissingLine { line, expected } => {
+                write!(f, "line {}: missing {}", line, expected)
+            }
+            RoverParseError::MissingToken {
+                line,
+                column,
+                expected,
+            } => write!(f, "line {}, column {}: missing {}", line, column, expected),
+            RoverParseError::UnexpectedToken {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: unexpected '{}'",
+                line, column, token
+            ),
+            RoverParseError::InvalidCoordinate {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid coordinate '{}'",
+                line, column, token
+            ),
+            RoverParseError::InvalidDirection {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid direction '{}'",
+                line, column, token
+            ),
+            RoverParseError::InvalidCommand {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid command '{}'",
+                line, column, token
+            ),
+            RoverParseError::OutsidePlateau {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: position '{}' is outside the plateau",
+                line, column, token
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RoverParseError {}
+
+#[derive(PartialEq)]
+enum Status {
+    Active,
+    Stopped,
+    Lost,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction {
     North,
     West,
     South,
@@ -37,23 +153,25 @@ enum Command {
     Left,
     Right,
     Move,
-    Null,
 }
 
-impl Command {
-    fn from(input: &char) -> Command {
+impl TryFrom<char> for Command {
+    type Error = ();
+
+    fn try_from(input: char) -> Result<Command, Self::Error> {
         match input {
-            'L' => Command::Left,
-            'R' => Command::Right,
-            'M' => Command::Move,
-            _ => Command::Null,
+            'L' => Ok(Command::Left),
+            'R' => Ok(Command::Right),
+            'M' => Ok(Command::Move),
+            _ => Err(()),
         }
     }
 }
 
-struct Coordinate {
-    x: u8,
-    y: u8,
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Coordinate {
+    x: u32,
+    y: u32,
 }
 
 impl fmt::Display for Coordinate {
@@ -63,28 +181,53 @@ impl fmt::Display for Coordinate {
 }
 
 impl Coordinate {
-    pub fn new(x: u8, y: u8) -> Self {
+    pub fn new(x: u32, y: u32) -> Self {
         Self { x, y }
     }
 
-    pub fn increment_y(&mut self) {
-        self.y += 1;
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn increment_y(&self) -> Option<Coordinate> {
+        Some(Coordinate::new(self.x, self.y.checked_add(1)?))
     }
 
-    pub fn decrement_y(&mut self) {
-        self.y -= 1;
+    pub fn decrement_y(&self) -> Option<Coordinate> {
+        Some(Coordinate::new(self.x, self.y.checked_sub(1)?))
     }
 
-    pub fn increment_x(&mut self) {
-        self.x += 1;
+    pub fn increment_x(&self) -> Option<Coordinate> {
+        Some(Coordinate::new(self.x.checked_add(1)?, self.y))
     }
 
-    pub fn decrement_x(&mut self) {
-        self.x -= 1;
+    pub fn decrement_x(&self) -> Option<Coordinate> {
+        Some(Coordinate::new(self.x.checked_sub(1)?, self.y))
     }
 }
 
-struct Position {
+#[derive(Clone, Copy)]
+struct Plateau {
+    width: u32,
+    height: u32,
+}
+
+impl Plateau {
+    fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    fn contains(&self, coordinate: &Coordinate) -> bool {
+        coordinate.x <= self.width && coordinate.y <= self.height
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Position {
     coordinate: Coordinate,
     direction: Direction,
 }
@@ -97,6 +240,14 @@ impl Position {
         }
     }
 
+    pub fn coordinate(&self) -> Coordinate {
+        self.coordinate
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
     fn turn_left(&mut self) {
         match self.direction {
             Direction::North => self.change_direction(Direction::West),
@@ -115,32 +266,36 @@ impl Position {
         }
     }
 
-    fn move_position(&mut self) {
+    fn next_coordinate(&self) -> Option<Coordinate> {
         match self.direction {
-            Direction::North => self.move_north(),
-            Direction::West => self.move_west(),
-            Direction::South => self.move_south(),
-            Direction::East => self.move_east(),
+            Direction::North => self.north(),
+            Direction::West => self.west(),
+            Direction::South => self.south(),
+            Direction::East => self.east(),
         }
     }
 
+    fn move_to(&mut self, coordinate: Coordinate) {
+        self.coordinate = coordinate;
+    }
+
     fn change_direction(&mut self, direction: Direction) {
         self.direction = direction;
     }
 
-    fn move_north(&mut self) {
+    fn north(&self) -> Option<Coordinate> {
         self.coordinate.increment_y()
     }
 
-    fn move_south(&mut self) {
+    fn south(&self) -> Option<Coordinate> {
         self.coordinate.decrement_y()
     }
 
-    fn move_east(&mut self) {
+    fn east(&self) -> Option<Coordinate> {
         self.coordinate.increment_x()
     }
 
-    pub fn move_west(&mut self) {
+    fn west(&self) -> Option<Coordinate> {
         self.coordinate.decrement_x()
     }
 }
@@ -151,6 +306,127 @@ impl fmt::Display for Position {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub enum StopReason {
+    Completed,
+    Stopped,
+    Lost,
+    Invalid(RoverParseError),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExecutionReport {
+    position: Position,
+    executed: usize,
+    skipped: usize,
+    stop_reason: StopReason,
+}
+
+impl ExecutionReport {
+    fn new(position: Position, executed: usize, skipped: usize, stop_reason: StopReason) -> Self {
+        Self {
+            position,
+            executed,
+            skipped,
+            stop_reason,
+        }
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn executed(&self) -> usize {
+        self.executed
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn stop_reason(&self) -> &StopReason {
+        &self.stop_reason
+    }
+}
+
+impl fmt::Display for ExecutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.stop_reason {
+            StopReason::Lost => write!(f, "{} LOST", self.position),
+            StopReason::Invalid(error) => write!(f, "{}", error),
+            _ => write!(f, "{}", self.position),
+        }
+    }
+}
+
+type Deployment = (Position, Vec<Command>);
+
+struct Tokens<'a> {
+    line: usize,
+    text: &'a str,
+    tokens: Vec<(usize, &'a str)>,
+    next: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: usize, text: &'a str) -> Self {
+        let tokens = text
+            .split_whitespace()
+            .map(|token| (Self::column_of(text, token), token))
+            .collect();
+
+        Self {
+            line,
+            text,
+            tokens,
+            next: 0,
+        }
+    }
+
+    fn column_of(text: &str, token: &str) -> usize {
+        let offset = token.as_ptr() as usize - text.as_ptr() as usize;
+        text[..offset].chars().count() + 1
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<(usize, &'a str), RoverParseError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .copied()
+            .ok_or(RoverParseError::MissingToken {
+                line: self.line,
+                column: self.text.chars().count() + 1,
+                expected,
+            })?;
+        self.next += 1;
+
+        Ok(token)
+    }
+
+    fn end(&self) -> Result<(), RoverParseError> {
+        match self.tokens.get(self.next) {
+            Some((column, token)) => Err(RoverParseError::UnexpectedToken {
+                line: self.line,
+                column: *column,
+                token: token.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn coordinate(&mut self, expected: &'static str) -> Result<u32, RoverParseError> {
+        let (column, token) = self.next(expected)?;
+
+        token
+            .parse()
+            .map_err(|_| RoverParseError::InvalidCoordinate {
+                line: self.line,
+                column,
+                token: token.to_string(),
+            })
+    }
+}
+
 pub struct Parser {}
 
 impl Default for Parser {
@@ -164,39 +440,102 @@ impl Parser {
         Parser {}
     }
 
-    fn parse(&self, instructions: String) -> (Position, Vec<Command>) {
-        let lines: Vec<&str> = instructions.lines().collect();
-        let position = self.parse_position(lines[1]);
+    fn parse(&self, instructions: String) -> Result<(Plateau, Vec<Deployment>), RoverParseError> {
+        let lines: Vec<&str> = instructions.trim_end().lines().collect();
+        let plateau_line = lines.first().ok_or(RoverParseError::MissingLine {
+            line: 1,
+            expected: "plateau size",
+        })?;
+        let plateau = self.parse_plateau(plateau_line)?;
+        let rovers = lines[1..]
+            .chunks(2)
+            .enumerate()
+            .map(|(index, rover)| self.parse_rover(&plateau, 2 * index + 2, rover))
+            .collect::<Result<_, _>>()?;
+
+        Ok((plateau, rovers))
+    }
+
+    fn parse_rover(
+        &self,
+        plateau: &Plateau,
+        line: usize,
+        lines: &[&str],
+    ) -> Result<Deployment, RoverParseError> {
+        let position = self.parse_position(plateau, line, lines[0])?;
 
-        if lines.len() < 3 {
-            return (position, Vec::new());
+        if lines.len() < 2 {
+            return Ok((position, Vec::new()));
         }
 
-        let commands = self.parse_commands(lines[2]);
+        let commands = self.parse_commands(line + 1, lines[1])?;
 
-        (position, commands)
+        Ok((position, commands))
     }
 
-    fn parse_position(&self, position: &str) -> Position {
-        let position_parts: Vec<&str> = position.split_whitespace().collect();
-        let x: u8 = position_parts[0].parse().unwrap_or(0);
-        let y: u8 = position_parts[1].parse().unwrap_or(0);
-        let direction = position_parts[2];
+    fn parse_plateau(&self, plateau: &str) -> Result<Plateau, RoverParseError> {
+        let mut tokens = Tokens::new(1, plateau);
+        let width = tokens.coordinate("plateau width")?;
+        let height = tokens.coordinate("plateau height")?;
+        tokens.end()?;
 
-        Position::new(
-            Coordinate::new(x, y),
-            Direction::from_str(direction).unwrap_or(Direction::North),
-        )
+        Ok(Plateau::new(width, height))
     }
 
-    fn parse_commands(&self, commands: &str) -> Vec<Command> {
-        let commands: Vec<char> = commands.chars().collect();
-        return commands.iter().map(Command::from).collect();
+    fn parse_position(
+        &self,
+        plateau: &Plateau,
+        line: usize,
+        position: &str,
+    ) -> Result<Position, RoverParseError> {
+        let mut tokens = Tokens::new(line, position);
+        let x = tokens.coordinate("x coordinate")?;
+        let y = tokens.coordinate("y coordinate")?;
+        let (column, direction) = tokens.next("direction")?;
+        tokens.end()?;
+
+        let coordinate = Coordinate::new(x, y);
+        if !plateau.contains(&coordinate) {
+            return Err(RoverParseError::OutsidePlateau {
+                line,
+                column: Tokens::column_of(position, position.trim_start()),
+                token: coordinate.to_string(),
+            });
+        }
+
+        let direction =
+            Direction::from_str(direction).map_err(|_| RoverParseError::InvalidDirection {
+                line,
+                column,
+                token: direction.to_string(),
+            })?;
+
+        Ok(Position::new(coordinate, direction))
+    }
+
+    fn parse_commands(&self, line: usize, commands: &str) -> Result<Vec<Command>, RoverParseError> {
+        commands
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(index, c)| {
+                Command::try_from(c).map_err(|_| RoverParseError::InvalidCommand {
+                    line,
+                    column: index + 1,
+                    token: c.to_string(),
+                })
+            })
+            .collect()
     }
 }
 
 pub struct Rover {
     position: Position,
+    plateau: Option<Plateau>,
+    edge_policy: EdgePolicy,
+    scents: HashSet<Coordinate>,
+    refused: usize,
+    status: Status,
     parser: Parser,
 }
 
@@ -204,24 +543,78 @@ impl Rover {
     pub fn new(parser: Parser) -> Self {
         Rover {
             position: Position::new(Coordinate::new(0, 0), Direction::North),
+            plateau: None,
+            edge_policy: EdgePolicy::Reject,
+            scents: HashSet::new(),
+            refused: 0,
+            status: Status::Active,
             parser,
         }
     }
 
-    pub fn execute(&mut self, instructions: String) -> String {
-        let (starting_position, commands) = self.parser.parse(instructions);
+    pub fn with_edge_policy(mut self, edge_policy: EdgePolicy) -> Self {
+        self.edge_policy = edge_policy;
+        self
+    }
+
+    pub fn execute(&mut self, instructions: String) -> ExecutionReport {
+        self.try_execute(instructions).unwrap_or_else(|error| {
+            ExecutionReport::new(self.position, 0, 0, StopReason::Invalid(error))
+        })
+    }
+
+    pub fn try_execute(
+        &mut self,
+        instructions: String,
+    ) -> Result<ExecutionReport, RoverParseError> {
+        let (plateau, mut rovers) = self.parser.parse(instructions)?;
+
+        if rovers.is_empty() {
+            return Err(RoverParseError::MissingLine {
+                line: 2,
+                expected: "rover position",
+            });
+        }
+
+        let (starting_position, commands) = rovers.remove(0);
+        self.update_plateau(plateau);
         self.update_position(starting_position);
 
+        Ok(self.run(commands))
+    }
+
+    fn run(&mut self, commands: Vec<Command>) -> ExecutionReport {
+        let total = commands.len();
+        let mut run = 0;
+        self.refused = 0;
+
         for c in commands {
+            if self.status != Status::Active {
+                break;
+            }
+
             match c {
                 Command::Left => self.turn_left(),
                 Command::Right => self.turn_right(),
                 Command::Move => self.move_rover(),
-                _ => {}
             }
+            run += 1;
         }
 
-        format!("{}", self.position)
+        let stop_reason = match self.status {
+            Status::Active => StopReason::Completed,
+            Status::Stopped => StopReason::Stopped,
+            Status::Lost => StopReason::Lost,
+        };
+        let executed = run - self.refused;
+
+        ExecutionReport::new(self.position, executed, total - executed, stop_reason)
+    }
+
+    fn update_plateau(&mut self, plateau: Plateau) {
+        self.plateau = Some(plateau);
+        self.scents.clear();
+        self.status = Status::Active;
     }
 
     fn update_position(&mut self, position: Position) {
@@ -237,7 +630,72 @@ impl Rover {
     }
 
     fn move_rover(&mut self) {
-        self.position.move_position();
+        match self
+            .position
+            .next_coordinate()
+            .filter(|c| self.plateau.is_some_and(|plateau| plateau.contains(c)))
+        {
+            Some(coordinate) => self.position.move_to(coordinate),
+            None => self.reach_edge(),
+        }
+    }
+
+    fn reach_edge(&mut self) {
+        self.refused += 1;
+        match self.edge_policy {
+            EdgePolicy::Stop => self.status = Status::Stopped,
+            EdgePolicy::Reject => {}
+            EdgePolicy::Lost => self.fall_off_edge(),
+        }
+    }
+
+    fn fall_off_edge(&mut self) {
+        if self.scents.insert(self.position.coordinate) {
+            self.status = Status::Lost;
+        }
+    }
+}
+
+pub struct Mission {
+    parser: Parser,
+    edge_policy: EdgePolicy,
+}
+
+impl Mission {
+    pub fn new(parser: Parser) -> Self {
+        Mission {
+            parser,
+            edge_policy: EdgePolicy::Reject,
+        }
+    }
+
+    pub fn with_edge_policy(mut self, edge_policy: EdgePolicy) -> Self {
+        self.edge_policy = edge_policy;
+        self
+    }
+
+    pub fn execute(&mut self, instructions: String) -> Vec<String> {
+        self.try_execute(instructions)
+            .unwrap_or_else(|error| vec![error.to_string()])
+    }
+
+    pub fn try_execute(&mut self, instructions: String) -> Result<Vec<String>, RoverParseError> {
+        let (plateau, rovers) = self.parser.parse(instructions)?;
+        let mut scents = HashSet::new();
+
+        Ok(rovers
+            .into_iter()
+            .map(|(starting_position, commands)| {
+                let mut rover = Rover::new(Parser::new()).with_edge_policy(self.edge_policy);
+                rover.update_plateau(plateau);
+                rover.update_position(starting_position);
+                rover.scents = std::mem::take(&mut scents);
+
+                let report = rover.run(commands);
+                scents = rover.scents;
+                report.to_string()
+            })
+            .collect())
     }
 }
 
@@ -250,7 +708,7 @@ mod mars_rover_unit_tests {
     fn stays_in_same_position_when_no_commands_are_sent() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\n"));
+        let position = rover.execute(String::from("5 5\n1 1 N\n")).to_string();
 
         assert_eq!(String::from("1 1 N"), position);
     }
@@ -259,7 +717,7 @@ mod mars_rover_unit_tests {
     fn turn_left_turns_from_north_to_west() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nL"));
+        let position = rover.execute(String::from("5 5\n1 1 N\nL")).to_string();
 
         assert_eq!(String::from("1 1 W"), position);
     }
@@ -268,7 +726,7 @@ mod mars_rover_unit_tests {
     fn turn_left_turns_from_west_to_south() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 W\nL"));
+        let position = rover.execute(String::from("5 5\n1 1 W\nL")).to_string();
 
         assert_eq!(String::from("1 1 S"), position);
     }
@@ -277,7 +735,7 @@ mod mars_rover_unit_tests {
     fn turn_left_turns_from_south_to_east() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 S\nL"));
+        let position = rover.execute(String::from("5 5\n1 1 S\nL")).to_string();
 
         assert_eq!(String::from("1 1 E"), position);
     }
@@ -286,7 +744,7 @@ mod mars_rover_unit_tests {
     fn turn_left_turns_from_east_to_north() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 E\nL"));
+        let position = rover.execute(String::from("5 5\n1 1 E\nL")).to_string();
 
         assert_eq!(String::from("1 1 N"), position);
     }
@@ -295,7 +753,7 @@ mod mars_rover_unit_tests {
     fn turn_left_four_time_ends_in_same_position() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nLLLL"));
+        let position = rover.execute(String::from("5 5\n1 1 N\nLLLL")).to_string();
 
         assert_eq!(String::from("1 1 N"), position);
     }
@@ -304,7 +762,7 @@ mod mars_rover_unit_tests {
     fn turn_right_turns_from_north_to_east() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nR"));
+        let position = rover.execute(String::from("5 5\n1 1 N\nR")).to_string();
 
         assert_eq!(String::from("1 1 E"), position);
     }
@@ -313,7 +771,7 @@ mod mars_rover_unit_tests {
     fn turn_right_turns_from_east_to_south() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 E\nR"));
+        let position = rover.execute(String::from("5 5\n1 1 E\nR")).to_string();
 
         assert_eq!(String::from("1 1 S"), position);
     }
@@ -322,7 +780,7 @@ mod mars_rover_unit_tests {
     fn turn_right_turns_from_south_to_west() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 S\nR"));
+        let position = rover.execute(String::from("5 5\n1 1 S\nR")).to_string();
 
         assert_eq!(String::from("1 1 W"), position);
     }
@@ -331,7 +789,7 @@ mod mars_rover_unit_tests {
     fn turn_right_turns_from_west_to_north() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 W\nR"));
+        let position = rover.execute(String::from("5 5\n1 1 W\nR")).to_string();
 
         assert_eq!(String::from("1 1 N"), position);
     }
@@ -340,7 +798,7 @@ mod mars_rover_unit_tests {
     fn turn_right_four_time_ends_in_same_position() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nRRRR"));
+        let position = rover.execute(String::from("5 5\n1 1 N\nRRRR")).to_string();
 
         assert_eq!(String::from("1 1 N"), position);
     }
@@ -349,7 +807,7 @@ mod mars_rover_unit_tests {
     fn move_north() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nM"));
+        let position = rover.execute(String::from("5 5\n1 1 N\nM")).to_string();
 
         assert_eq!(String::from("1 2 N"), position);
     }
@@ -358,7 +816,7 @@ mod mars_rover_unit_tests {
     fn move_south() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 S\nM"));
+        let position = rover.execute(String::from("5 5\n1 1 S\nM")).to_string();
 
         assert_eq!(String::from("1 0 S"), position);
     }
@@ -367,7 +825,7 @@ mod mars_rover_unit_tests {
     fn move_west() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 W\nM"));
+        let position = rover.execute(String::from("5 5\n1 1 W\nM")).to_string();
 
         assert_eq!(String::from("0 1 W"), position);
     }
@@ -376,7 +834,7 @@ mod mars_rover_unit_tests {
     fn move_east() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 E\nM"));
+        let position = rover.execute(String::from("5 5\n1 1 E\nM")).to_string();
 
         assert_eq!(String::from("2 1 E"), position);
     }
@@ -391,7 +849,9 @@ mod mars_rover_acceptance_tests {
     fn turn_left_and_move() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 2 N\nLMLMLMLMM"));
+        let position = rover
+            .execute(String::from("5 5\n1 2 N\nLMLMLMLMM"))
+            .to_string();
 
         assert_eq!(String::from("1 3 N"), position);
     }
@@ -400,8 +860,452 @@ mod mars_rover_acceptance_tests {
     fn turn_rigth_and_move() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n3 3 E\nMMRMMRMRRM"));
+        let position = rover
+            .execute(String::from("5 5\n3 3 E\nMMRMMRMRRM"))
+            .to_string();
 
         assert_eq!(String::from("5 1 E"), position);
     }
 }
+
+#[cfg(test)]
+mod mars_rover_plateau_tests {
+    use crate::mars_rover::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("5 5\n1 5 N\nMR", "1 5 E" ; "north edge")]
+    #[test_case("5 5\n1 0 S\nMR", "1 0 W" ; "south edge")]
+    #[test_case("5 5\n5 1 E\nMR", "5 1 S" ; "east edge")]
+    #[test_case("5 5\n0 1 W\nMR", "0 1 N" ; "west edge")]
+    fn reject_policy_skips_the_move_and_keeps_executing(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Reject);
+
+        let position = rover.execute(String::from(instructions)).to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test_case("5 5\n1 5 N\nMR", "1 5 N" ; "north edge")]
+    #[test_case("5 5\n1 0 S\nMR", "1 0 S" ; "south edge")]
+    #[test_case("5 5\n5 1 E\nMR", "5 1 E" ; "east edge")]
+    #[test_case("5 5\n0 1 W\nMR", "0 1 W" ; "west edge")]
+    fn stop_policy_halts_the_rover_at_the_edge(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Stop);
+
+        let position = rover.execute(String::from(instructions)).to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test_case("5 5\n1 5 N\nMR", "1 5 N LOST" ; "north edge")]
+    #[test_case("5 5\n1 0 S\nMR", "1 0 S LOST" ; "south edge")]
+    #[test_case("5 5\n5 1 E\nMR", "5 1 E LOST" ; "east edge")]
+    #[test_case("5 5\n0 1 W\nMR", "0 1 W LOST" ; "west edge")]
+    fn lost_policy_reports_the_rover_as_lost(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        let position = rover.execute(String::from(instructions)).to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test]
+    fn reject_is_the_default_edge_policy() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("2 2\n0 0 S\nMLMMMM"))
+            .to_string();
+
+        assert_eq!(String::from("2 0 E"), position);
+    }
+
+    #[test]
+    fn does_not_overflow_at_the_largest_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from(
+                "4294967295 4294967295\n4294967295 4294967295 N\nMRM",
+            ))
+            .to_string();
+
+        assert_eq!(String::from("4294967295 4294967295 E"), position);
+    }
+
+    #[test]
+    fn drives_beyond_the_old_eight_bit_limit() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("1000 1000\n254 300 E\nMMMLM"))
+            .to_string();
+
+        assert_eq!(String::from("257 301 N"), position);
+    }
+
+    #[test]
+    fn lost_rover_is_active_again_on_next_execution() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        rover.execute(String::from("5 5\n0 0 S\nM"));
+        let position = rover.execute(String::from("5 5\n0 0 N\nM")).to_string();
+
+        assert_eq!(String::from("0 1 N"), position);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_mission_tests {
+    use crate::mars_rover::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn executes_the_canonical_two_rover_example() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions = mission.execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMMRMMRMRRM"));
+
+        assert_eq!(vec!["1 3 N", "5 1 E"], positions);
+    }
+
+    #[test]
+    fn reports_one_line_per_rover() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions =
+            mission.execute(String::from("5 5\n0 0 N\nM\n1 1 E\nM\n2 2 S\nM\n3 3 W\nM"));
+
+        assert_eq!(vec!["0 1 N", "2 1 E", "2 1 S", "2 3 W"], positions);
+    }
+
+    #[test]
+    fn last_rover_may_omit_its_command_line() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions = mission.execute(String::from("5 5\n1 2 N\nM\n3 3 E"));
+
+        assert_eq!(vec!["1 3 N", "3 3 E"], positions);
+    }
+
+    #[test]
+    fn rovers_share_the_plateau_and_edge_policy() {
+        let mut mission = Mission::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        let positions = mission.execute(String::from("1 1\n0 0 N\nMM\n1 1 E\nLM"));
+
+        assert_eq!(vec!["0 1 N LOST", "1 1 N LOST"], positions);
+    }
+
+    #[test]
+    fn rover_executes_only_the_first_rover_of_a_mission() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMMRMMRMRRM"))
+            .to_string();
+
+        assert_eq!(String::from("1 3 N"), position);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_parse_error_tests {
+    use crate::mars_rover::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn empty_instructions_are_missing_the_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from(""));
+
+        assert_eq!(
+            Err(RoverParseError::MissingLine {
+                line: 1,
+                expected: "plateau size"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn rover_needs_a_position_line() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n"));
+
+        assert_eq!(
+            Err(RoverParseError::MissingLine {
+                line: 2,
+                expected: "rover position"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn plateau_needs_a_height() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5\n1 1 N"));
+
+        assert_eq!(
+            Err(RoverParseError::MissingToken {
+                line: 1,
+                column: 2,
+                expected: "plateau height"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn coordinates_must_be_numbers() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 y N\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCoordinate {
+                line: 2,
+                column: 3,
+                token: String::from("y")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn coordinates_must_fit_the_coordinate_type() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 4294967296\n1 1 N"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCoordinate {
+                line: 1,
+                column: 3,
+                token: String::from("4294967296")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn direction_must_be_a_compass_point() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 1 X\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidDirection {
+                line: 2,
+                column: 5,
+                token: String::from("X")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn position_line_rejects_extra_tokens() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 1 N E\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::UnexpectedToken {
+                line: 2,
+                column: 7,
+                token: String::from("E")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn position_must_be_on_the_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n6 1 N\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::OutsidePlateau {
+                line: 2,
+                column: 1,
+                token: String::from("6 1")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn unknown_commands_are_rejected() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 1 N\nMMXM"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 3,
+                column: 3,
+                token: String::from("X")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_rover_that_caused_them() {
+        let mut mission = Mission::new(Parser::new());
+
+        let result = mission.try_execute(String::from("5 5\n1 2 N\nLM\n3 3 E\nMMRQ"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 5,
+                column: 4,
+                token: String::from("Q")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn execute_reports_the_error_as_text() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nMMXM")).to_string();
+
+        assert_eq!(
+            String::from("line 3, column 3: invalid command 'X'"),
+            position
+        );
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_scent_tests {
+    use crate::mars_rover::*;
+    use pretty_assertions::assert_eq;
+
+    fn martian_robots() -> Mission {
+        Mission::new(Parser::new()).with_edge_policy(EdgePolicy::Lost)
+    }
+
+    #[test]
+    fn executes_the_martian_robots_sample() {
+        let mut mission = martian_robots();
+
+        let positions = mission.execute(String::from(
+            "5 3\n1 1 E\nRMRMRMRM\n3 2 N\nMRRMLLMMRRMLL\n0 3 W\nLLMMMLMLML",
+        ));
+
+        assert_eq!(vec!["1 1 E", "3 3 N LOST", "2 3 S"], positions);
+    }
+
+    #[test]
+    fn lost_rover_leaves_a_scent_at_its_last_position() {
+        let mut mission = martian_robots();
+
+        let positions = mission.execute(String::from("2 2\n1 2 N\nM\n1 1 N\nMMR"));
+
+        assert_eq!(vec!["1 2 N LOST", "1 2 E"], positions);
+    }
+
+    #[test]
+    fn scent_ignores_moves_off_the_grid_in_any_direction() {
+        let mut mission = martian_robots();
+
+        let positions = mission.execute(String::from("2 2\n2 2 N\nM\n2 2 E\nMLMLM"));
+
+        assert_eq!(vec!["2 2 N LOST", "1 2 W"], positions);
+    }
+
+    #[test]
+    fn scent_does_not_stop_moves_that_stay_on_the_grid() {
+        let mut mission = martian_robots();
+
+        let positions = mission.execute(String::from("2 2\n0 0 S\nM\n0 1 S\nMM"));
+
+        assert_eq!(vec!["0 0 S LOST", "0 0 S"], positions);
+    }
+
+    #[test]
+    fn scent_only_protects_the_cell_it_was_left_on() {
+        let mut mission = martian_robots();
+
+        let positions = mission.execute(String::from("2 2\n0 0 S\nM\n1 0 S\nM"));
+
+        assert_eq!(vec!["0 0 S LOST", "1 0 S LOST"], positions);
+    }
+
+    #[test]
+    fn scents_do_not_carry_over_to_the_next_mission() {
+        let mut mission = martian_robots();
+
+        mission.execute(String::from("2 2\n0 0 S\nM"));
+        let positions = mission.execute(String::from("2 2\n0 0 S\nM"));
+
+        assert_eq!(vec!["0 0 S LOST"], positions);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_report_tests {
+    use crate::mars_rover::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test]
+    fn reports_the_final_position() {
+        let mut rover = Rover::new(Parser::new());
+
+        let report = rover.execute(String::from("5 5\n1 2 N\nLMLMLMLMM"));
+        let position = report.position();
+
+        assert_eq!(
+            (1, 3),
+            (position.coordinate().x(), position.coordinate().y())
+        );
+        assert_eq!(Direction::North, position.direction());
+        assert_eq!(&StopReason::Completed, report.stop_reason());
+        assert_eq!((9, 0), (report.executed(), report.skipped()));
+    }
+
+    #[test_case(EdgePolicy::Reject, 2, 1, StopReason::Completed ; "reject")]
+    #[test_case(EdgePolicy::Stop, 0, 3, StopReason::Stopped ; "stop")]
+    #[test_case(EdgePolicy::Lost, 0, 3, StopReason::Lost ; "lost")]
+    fn counts_refused_moves_as_skipped(
+        edge_policy: EdgePolicy,
+        executed: usize,
+        skipped: usize,
+        stop_reason: StopReason,
+    ) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(edge_policy);
+
+        let report = rover.execute(String::from("5 5\n1 5 N\nMRM"));
+
+        assert_eq!(
+            (executed, skipped, &stop_reason),
+            (report.executed(), report.skipped(), report.stop_reason())
+        );
+    }
+
+    #[test]
+    fn reports_invalid_instructions_as_the_stop_reason() {
+        let mut rover = Rover::new(Parser::new());
+        let error = RoverParseError::InvalidDirection {
+            line: 2,
+            column: 5,
+            token: String::from("X"),
+        };
+
+        let report = rover.execute(String::from("5 5\n1 1 X\nM"));
+
+        assert_eq!(&StopReason::Invalid(error.clone()), report.stop_reason());
+        assert_eq!(error.to_string(), report.to_string());
+    }
+}
@@ -1,12 +1,373 @@
+mod optimizer;
+mod planner;
+mod render;
+mod svg;
+#[cfg(test)]
+mod test_support;
+
+pub use planner::{Coverage, CoveragePlanner, ReturnStrategy, RoutePlanner};
+
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::ops::Neg;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EdgePolicy {
+    Stop,
+    Reject,
+    Lost,
+    Wrap,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Compass {
+    FourWay,
+    EightWay,
+    Hex,
+}
+
+impl Compass {
+    fn points(&self) -> usize {
+        match self {
+            Compass::FourWay => 4,
+            _ => self.rose().len(),
+        }
+    }
+
+    fn rose(&self) -> &'static [(&'static str, i8, i8, char)] {
+        match self {
+            Compass::FourWay => &[],
+            Compass::EightWay => &COMPASS_ROSE,
+            Compass::Hex => &HEX_ROSE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CollisionPolicy {
+    Ignore,
+    Reject,
+    Abort,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RoverParseError {
+    MissingLine {
+        line: usize,
+        expected: &'static str,
+    },
+    MissingToken {
+        line: usize,
+        column: usize,
+        expected: &'static str,
+    },
+    UnexpectedToken {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    InvalidCoordinate {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    InvalidDirection {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    InvalidCommand {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    OutsidePlateau {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    InvalidMapCell {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    MapSizeMismatch {
+        line: usize,
+        column: usize,
+        token: String,
+        columns: usize,
+        rows: usize,
+    },
+    OnObstacle {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    UnbalancedParenthesis {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    ExpansionLimitExceeded {
+        line: usize,
+        column: usize,
+        token: String,
+        limit: usize,
+    },
+    NestingTooDeep {
+        line: usize,
+        column: usize,
+        token: String,
+        limit: usize,
+    },
+    InvalidDefinition {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    DuplicateDefinition {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    RecursiveDefinition {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+}
+
+impl fmt::Display for RoverParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RoverParseError::MissingLine { line, expected } => {
+                write!(f, "line {}: missing {}", line, expected)
+            }
+            RoverParseError::MissingToken {
+                line,
+                column,
+                expected,
+            } => write!(f, "line {}, column {}: missing {}", line, column, expected),
+            RoverParseError::UnexpectedToken {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: unexpected '{}'",
+                line, column, token
+            ),
+            RoverParseError::InvalidCoordinate {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid coordinate '{}'",
+                line, column, token
+            ),
+            RoverParseError::InvalidDirection {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid direction '{}'",
+                line, column, token
+            ),
+            RoverParseError::InvalidCommand {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid command '{}'",
+                line, column, token
+            ),
+            RoverParseError::OutsidePlateau {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: position '{}' is outside the plateau",
+                line, column, token
+            ),
+            RoverParseError::InvalidMapCell {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid map cell '{}'",
+                line, column, token
+            ),
+            RoverParseError::MapSizeMismatch {
+                line,
+                column,
+                token,
+                columns,
+                rows,
+            } => write!(
+                f,
+                "line {}, column {}: plateau '{}' does not match the {}x{} obstacle map",
+                line, column, token, columns, rows
+            ),
+            RoverParseError::OnObstacle {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: position '{}' is on an obstacle",
+                line, column, token
+            ),
+            RoverParseError::UnbalancedParenthesis {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: unbalanced '{}'",
+                line, column, token
+            ),
+            RoverParseError::ExpansionLimitExceeded {
+                line,
+                column,
+                token,
+                limit,
+            } => write!(
+                f,
+                "line {}, column {}: '{}' expands to more than {} commands",
+                line, column, token, limit
+            ),
+            RoverParseError::NestingTooDeep {
+                line,
+                column,
+                token,
+                limit,
+            } => write!(
+                f,
+                "line {}, column {}: '{}' nests more than {} levels deep",
+                line, column, token, limit
+            ),
+            RoverParseError::InvalidDefinition {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid definition name '{}'",
+                line, column, token
+            ),
+            RoverParseError::DuplicateDefinition {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: '{}' is already defined",
+                line, column, token
+            ),
+            RoverParseError::RecursiveDefinition {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: recursive definition {}",
+                line, column, token
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RoverParseError {}
+
+const MAX_RENDERED_CELLS: u64 = 1_000_000;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RenderError {
+    PlateauTooLarge { columns: u64, rows: u64, limit: u64 },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::PlateauTooLarge {
+                columns,
+                rows,
+                limit,
+            } => write!(
+                f,
+                "plateau of {} x {} cells is larger than the {} cells that can be rendered",
+                columns, rows, limit
+            ),
+        }
+    }
+}
 
-struct DirectionMoveVector(i8, i8);
+impl std::error::Error for RenderError {}
 
-trait Direction {
+#[derive(PartialEq)]
+enum Status {
+    Active,
+    Stopped,
+    Lost,
+    Blocked,
+    Collided,
+    Depleted,
+}
+
+pub struct DirectionMoveVector(i8, i8, i8);
+
+impl Neg for DirectionMoveVector {
+    type Output = DirectionMoveVector;
+
+    fn neg(self) -> Self::Output {
+        DirectionMoveVector(-self.0, -self.1, -self.2)
+    }
+}
+
+pub trait Direction {
     fn turn_left(&self) -> Box<dyn Direction>;
     fn turn_right(&self) -> Box<dyn Direction>;
     fn move_vector(&self) -> DirectionMoveVector;
     fn to_string(&self) -> String;
+    fn arrow(&self) -> char;
+    fn compass(&self) -> Compass;
+    fn boxed(&self) -> Box<dyn Direction>;
+
+    fn level(&self) -> Box<dyn Direction> {
+        self.boxed()
+    }
+
+    fn pitch_up(&self) -> Box<dyn Direction> {
+        Box::new(Pitched::new(self.boxed(), Pitch::Up))
+    }
+
+    fn pitch_down(&self) -> Box<dyn Direction> {
+        Box::new(Pitched::new(self.boxed(), Pitch::Down))
+    }
+}
+
+impl Clone for Box<dyn Direction> {
+    fn clone(&self) -> Self {
+        self.boxed()
+    }
+}
+
+impl PartialEq for dyn Direction {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+impl fmt::Debug for dyn Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string())
+    }
 }
 
 struct North;
@@ -22,443 +383,4403 @@ impl Direction for North {
         Box::new(East)
     }
 
-    fn move_vector(&self) -> DirectionMoveVector {
-        DirectionMoveVector(0, 1)
-    }
+    fn move_vector(&self) -> DirectionMoveVector {
+        DirectionMoveVector(0, 1, 0)
+    }
+
+    fn to_string(&self) -> String {
+        String::from("N")
+    }
+
+    fn arrow(&self) -> char {
+        '^'
+    }
+
+    fn compass(&self) -> Compass {
+        Compass::FourWay
+    }
+
+    fn boxed(&self) -> Box<dyn Direction> {
+        Box::new(North)
+    }
+}
+
+impl Direction for South {
+    fn turn_left(&self) -> Box<dyn Direction> {
+        Box::new(East)
+    }
+    fn turn_right(&self) -> Box<dyn Direction> {
+        Box::new(West)
+    }
+
+    fn move_vector(&self) -> DirectionMoveVector {
+        DirectionMoveVector(0, -1, 0)
+    }
+
+    fn to_string(&self) -> String {
+        String::from("S")
+    }
+
+    fn arrow(&self) -> char {
+        'v'
+    }
+
+    fn compass(&self) -> Compass {
+        Compass::FourWay
+    }
+
+    fn boxed(&self) -> Box<dyn Direction> {
+        Box::new(South)
+    }
+}
+
+impl Direction for East {
+    fn turn_left(&self) -> Box<dyn Direction> {
+        Box::new(North)
+    }
+
+    fn turn_right(&self) -> Box<dyn Direction> {
+        Box::new(South)
+    }
+
+    fn move_vector(&self) -> DirectionMoveVector {
+        DirectionMoveVector(1, 0, 0)
+    }
+
+    fn to_string(&self) -> String {
+        String::from("E")
+    }
+
+    fn arrow(&self) -> char {
+        '>'
+    }
+
+    fn compass(&self) -> Compass {
+        Compass::FourWay
+    }
+
+    fn boxed(&self) -> Box<dyn Direction> {
+        Box::new(East)
+    }
+}
+
+impl Direction for West {
+    fn turn_left(&self) -> Box<dyn Direction> {
+        Box::new(South)
+    }
+    fn turn_right(&self) -> Box<dyn Direction> {
+        Box::new(North)
+    }
+
+    fn move_vector(&self) -> DirectionMoveVector {
+        DirectionMoveVector(-1, 0, 0)
+    }
+
+    fn to_string(&self) -> String {
+        String::from("W")
+    }
+
+    fn arrow(&self) -> char {
+        '<'
+    }
+
+    fn compass(&self) -> Compass {
+        Compass::FourWay
+    }
+
+    fn boxed(&self) -> Box<dyn Direction> {
+        Box::new(West)
+    }
+}
+
+const COMPASS_ROSE: [(&str, i8, i8, char); 8] = [
+    ("N", 0, 1, '^'),
+    ("NE", 1, 1, '↗'),
+    ("E", 1, 0, '>'),
+    ("SE", 1, -1, '↘'),
+    ("S", 0, -1, 'v'),
+    ("SW", -1, -1, '↙'),
+    ("W", -1, 0, '<'),
+    ("NW", -1, 1, '↖'),
+];
+
+const HEX_ROSE: [(&str, i8, i8, char); 6] = [
+    ("E", 1, 0, '>'),
+    ("SE", 1, -1, '↘'),
+    ("SW", 0, -1, '↙'),
+    ("W", -1, 0, '<'),
+    ("NW", -1, 1, '↖'),
+    ("NE", 0, 1, '↗'),
+];
+
+#[derive(Clone, Copy)]
+struct CompassPoint {
+    compass: Compass,
+    point: usize,
+}
+
+impl CompassPoint {
+    fn new(compass: Compass, point: usize) -> Self {
+        Self { compass, point }
+    }
+
+    fn turn(&self, by: usize) -> Box<dyn Direction> {
+        let points = self.compass.points();
+
+        Box::new(CompassPoint::new(self.compass, (self.point + by) % points))
+    }
+}
+
+impl Direction for CompassPoint {
+    fn turn_left(&self) -> Box<dyn Direction> {
+        self.turn(self.compass.points() - 1)
+    }
+
+    fn turn_right(&self) -> Box<dyn Direction> {
+        self.turn(1)
+    }
+
+    fn move_vector(&self) -> DirectionMoveVector {
+        let (_, x, y, _) = self.compass.rose()[self.point];
+        DirectionMoveVector(x, y, 0)
+    }
+
+    fn to_string(&self) -> String {
+        String::from(self.compass.rose()[self.point].0)
+    }
+
+    fn arrow(&self) -> char {
+        self.compass.rose()[self.point].3
+    }
+
+    fn compass(&self) -> Compass {
+        self.compass
+    }
+
+    fn boxed(&self) -> Box<dyn Direction> {
+        Box::new(*self)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Pitch {
+    Up,
+    Down,
+}
+
+#[derive(Clone)]
+struct Pitched {
+    level: Box<dyn Direction>,
+    pitch: Pitch,
+}
+
+impl Pitched {
+    fn new(level: Box<dyn Direction>, pitch: Pitch) -> Self {
+        Self { level, pitch }
+    }
+}
+
+impl Direction for Pitched {
+    fn turn_left(&self) -> Box<dyn Direction> {
+        Box::new(Pitched::new(self.level.turn_left(), self.pitch))
+    }
+
+    fn turn_right(&self) -> Box<dyn Direction> {
+        Box::new(Pitched::new(self.level.turn_right(), self.pitch))
+    }
+
+    fn move_vector(&self) -> DirectionMoveVector {
+        match self.pitch {
+            Pitch::Up => DirectionMoveVector(0, 0, 1),
+            Pitch::Down => DirectionMoveVector(0, 0, -1),
+        }
+    }
+
+    fn to_string(&self) -> String {
+        match self.pitch {
+            Pitch::Up => format!("{} UP", self.level.to_string()),
+            Pitch::Down => format!("{} DOWN", self.level.to_string()),
+        }
+    }
+
+    fn arrow(&self) -> char {
+        match self.pitch {
+            Pitch::Up => '+',
+            Pitch::Down => '-',
+        }
+    }
+
+    fn compass(&self) -> Compass {
+        self.level.compass()
+    }
+
+    fn boxed(&self) -> Box<dyn Direction> {
+        Box::new(self.clone())
+    }
+
+    fn level(&self) -> Box<dyn Direction> {
+        self.level.clone()
+    }
+
+    fn pitch_up(&self) -> Box<dyn Direction> {
+        match self.pitch {
+            Pitch::Up => self.boxed(),
+            Pitch::Down => self.level.clone(),
+        }
+    }
+
+    fn pitch_down(&self) -> Box<dyn Direction> {
+        match self.pitch {
+            Pitch::Up => self.level.clone(),
+            Pitch::Down => self.boxed(),
+        }
+    }
+}
+
+struct DirectionFactory;
+
+impl DirectionFactory {
+    fn create(compass: Compass, input: &str) -> Option<Box<dyn Direction>> {
+        match compass {
+            Compass::FourWay => match input {
+                "N" => Some(Box::new(North)),
+                "W" => Some(Box::new(West)),
+                "S" => Some(Box::new(South)),
+                "E" => Some(Box::new(East)),
+                _ => None,
+            },
+            _ => compass
+                .rose()
+                .iter()
+                .position(|(name, ..)| *name == input)
+                .map(|point| Box::new(CompassPoint::new(compass, point)) as Box<dyn Direction>),
+        }
+    }
+}
+
+trait RoverCommand {
+    fn execute(&self, rover: &mut Rover);
+    fn symbol(&self) -> char;
+    fn energy(&self, rover: &Rover) -> u32;
+}
+
+struct MoveForward;
+
+struct MoveBackward;
+
+struct TurnLeft;
+
+struct TurnRight;
+
+struct UTurn;
+
+struct Hold;
+
+struct Climb;
+
+struct Descend;
+
+impl RoverCommand for MoveForward {
+    fn execute(&self, rover: &mut Rover) {
+        rover.move_forward();
+    }
+    fn symbol(&self) -> char {
+        'M'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.move_energy(rover.position.direction.move_vector())
+    }
+}
+
+impl RoverCommand for MoveBackward {
+    fn execute(&self, rover: &mut Rover) {
+        rover.move_backward();
+    }
+    fn symbol(&self) -> char {
+        'B'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.move_energy(-rover.position.direction.move_vector())
+    }
+}
+
+impl RoverCommand for TurnLeft {
+    fn execute(&self, rover: &mut Rover) {
+        rover.turn_left();
+    }
+    fn symbol(&self) -> char {
+        'L'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.energy_costs.turn
+    }
+}
+
+impl RoverCommand for TurnRight {
+    fn execute(&self, rover: &mut Rover) {
+        rover.turn_right();
+    }
+    fn symbol(&self) -> char {
+        'R'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.energy_costs.turn
+    }
+}
+
+impl RoverCommand for UTurn {
+    fn execute(&self, rover: &mut Rover) {
+        rover.u_turn();
+    }
+    fn symbol(&self) -> char {
+        'U'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.energy_costs.turn.saturating_mul(2)
+    }
+}
+
+impl RoverCommand for Hold {
+    fn execute(&self, _: &mut Rover) {}
+
+    fn symbol(&self) -> char {
+        'H'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.energy_costs.hold
+    }
+}
+
+impl RoverCommand for Climb {
+    fn execute(&self, rover: &mut Rover) {
+        rover.climb();
+    }
+
+    fn symbol(&self) -> char {
+        'C'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.energy_costs.turn
+    }
+}
+
+impl RoverCommand for Descend {
+    fn execute(&self, rover: &mut Rover) {
+        rover.descend();
+    }
+
+    fn symbol(&self) -> char {
+        'D'
+    }
+
+    fn energy(&self, rover: &Rover) -> u32 {
+        rover.energy_costs.turn
+    }
+}
+
+struct Commands {
+    commands: Vec<Box<dyn RoverCommand>>,
+}
+
+impl Commands {
+    fn new() -> Self {
+        Self { commands: vec![] }
+    }
+
+    fn add(&mut self, cmd: Box<dyn RoverCommand>) {
+        self.commands.push(cmd);
+    }
+
+    fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    fn execute(&self, rover: &mut Rover) {
+        for command in &self.commands {
+            if !rover.is_active() {
+                break;
+            }
+
+            rover.consume(command.energy(rover));
+            if !rover.is_active() {
+                break;
+            }
+
+            command.execute(rover);
+            rover.record(command.symbol());
+        }
+    }
+
+    fn create_command(input: &char) -> Option<Box<dyn RoverCommand>> {
+        match input {
+            'M' => Some(Box::new(MoveForward)),
+            'B' => Some(Box::new(MoveBackward)),
+            'L' => Some(Box::new(TurnLeft)),
+            'R' => Some(Box::new(TurnRight)),
+            'U' => Some(Box::new(UTurn)),
+            'H' => Some(Box::new(Hold)),
+            'C' => Some(Box::new(Climb)),
+            'D' => Some(Box::new(Descend)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Coordinate {
+    x: u32,
+    y: u32,
+    z: Option<u32>,
+}
+
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.z {
+            Some(z) => write!(f, "{} {} {}", self.x, self.y, z),
+            None => write!(f, "{} {}", self.x, self.y),
+        }
+    }
+}
+
+impl Coordinate {
+    fn new(x: u32, y: u32) -> Self {
+        Self { x, y, z: None }
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn z(&self) -> Option<u32> {
+        self.z
+    }
+
+    fn with_altitude(mut self, z: Option<u32>) -> Self {
+        self.z = z;
+        self
+    }
+
+    fn footprint(&self) -> Coordinate {
+        Coordinate::new(self.x, self.y)
+    }
+
+    fn on_ground(&self) -> bool {
+        self.z.unwrap_or(0) == 0
+    }
+
+    fn apply_vector(&self, vector: DirectionMoveVector) -> Option<Coordinate> {
+        let z = match (self.z, vector.2) {
+            (Some(z), dz) => Some(z.checked_add_signed(dz.into())?),
+            (None, 0) => None,
+            (None, _) => return None,
+        };
+
+        Some(
+            Coordinate::new(
+                self.x.checked_add_signed(vector.0.into())?,
+                self.y.checked_add_signed(vector.1.into())?,
+            )
+            .with_altitude(z),
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Plateau {
+    width: u32,
+    height: u32,
+    altitude: Option<u32>,
+}
+
+impl Plateau {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            altitude: None,
+        }
+    }
+
+    fn with_altitude(mut self, altitude: Option<u32>) -> Self {
+        self.altitude = altitude;
+        self
+    }
+
+    fn contains(&self, coordinate: &Coordinate) -> bool {
+        let within_altitude = match (coordinate.z, self.altitude) {
+            (Some(z), Some(altitude)) => z <= altitude,
+            (None, None) => true,
+            _ => false,
+        };
+
+        coordinate.x <= self.width && coordinate.y <= self.height && within_altitude
+    }
+
+    fn wrap(&self, coordinate: &Coordinate, vector: DirectionMoveVector) -> Coordinate {
+        Coordinate::new(
+            Self::wrap_axis(coordinate.x, vector.0, self.width),
+            Self::wrap_axis(coordinate.y, vector.1, self.height),
+        )
+        .with_altitude(
+            coordinate
+                .z
+                .map(|z| Self::wrap_axis(z, vector.2, self.altitude.unwrap_or(0))),
+        )
+    }
+
+    fn wrap_axis(value: u32, delta: i8, max: u32) -> u32 {
+        (value as i64 + delta as i64).rem_euclid(max as i64 + 1) as u32
+    }
+
+    fn renderable(&self) -> Result<(), RenderError> {
+        let columns = self.width as u64 + 1;
+        let rows = self.height as u64 + 1;
+
+        match columns.checked_mul(rows) {
+            Some(cells) if cells <= MAX_RENDERED_CELLS => Ok(()),
+            _ => Err(RenderError::PlateauTooLarge {
+                columns,
+                rows,
+                limit: MAX_RENDERED_CELLS,
+            }),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Collision {
+    rover: usize,
+    other: usize,
+    coordinate: Coordinate,
+}
+
+impl Collision {
+    fn new(rover: usize, other: usize, coordinate: Coordinate) -> Self {
+        Self {
+            rover,
+            other,
+            coordinate,
+        }
+    }
+
+    pub fn rover(&self) -> usize {
+        self.rover
+    }
+
+    pub fn other(&self) -> usize {
+        self.other
+    }
+
+    pub fn cell(&self) -> (u32, u32) {
+        (self.coordinate.x, self.coordinate.y)
+    }
+}
+
+impl fmt::Display for Collision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rover {} collided with rover {} at {}",
+            self.rover, self.other, self.coordinate
+        )
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ObstacleMap {
+    obstacles: HashSet<Coordinate>,
+    size: Option<(usize, usize)>,
+}
+
+impl ObstacleMap {
+    fn is_blocked(&self, coordinate: &Coordinate) -> bool {
+        coordinate.on_ground() && self.obstacles.contains(&coordinate.footprint())
+    }
+
+    fn fits(&self, plateau: &Plateau) -> bool {
+        self.size.is_none_or(|(columns, rows)| {
+            columns as u64 == u64::from(plateau.width) + 1
+                && rows as u64 == u64::from(plateau.height) + 1
+        })
+    }
+}
+
+impl FromStr for ObstacleMap {
+    type Err = RoverParseError;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        let obstacles = parse_map(map, |cell| match cell {
+            '.' => Some(None),
+            '#' => Some(Some(())),
+            _ => None,
+        })?;
+        let rows: Vec<&str> = map.trim_end().lines().collect();
+        let columns = rows.iter().map(|row| row.chars().count()).max();
+
+        Ok(Self {
+            obstacles: obstacles
+                .into_iter()
+                .map(|(coordinate, _)| coordinate)
+                .collect(),
+            size: columns.map(|columns| (columns, rows.len())),
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Terrain {
+    Sand,
+    Rock,
+    Crater,
+}
+
+#[derive(Clone)]
+pub struct TerrainMap {
+    cells: HashMap<Coordinate, Terrain>,
+    costs: HashMap<Terrain, Option<u32>>,
+}
+
+impl Default for TerrainMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TerrainMap {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            costs: HashMap::from([
+                (Terrain::Sand, Some(1)),
+                (Terrain::Rock, Some(3)),
+                (Terrain::Crater, None),
+            ]),
+        }
+    }
+
+    pub fn with_cost(mut self, terrain: Terrain, cost: u32) -> Self {
+        self.costs.insert(terrain, Some(cost));
+        self
+    }
+
+    pub fn with_impassable(mut self, terrain: Terrain) -> Self {
+        self.costs.insert(terrain, None);
+        self
+    }
+
+    fn cost(&self, coordinate: &Coordinate) -> Option<u32> {
+        let terrain = match coordinate.on_ground() {
+            true => self
+                .cells
+                .get(&coordinate.footprint())
+                .unwrap_or(&Terrain::Sand),
+            false => &Terrain::Sand,
+        };
+
+        self.costs[terrain]
+    }
+
+    fn is_passable(&self, coordinate: &Coordinate) -> bool {
+        self.cost(coordinate).is_some()
+    }
+
+    fn cheapest(&self) -> u32 {
+        self.costs.values().flatten().copied().min().unwrap_or(0)
+    }
+}
+
+impl FromStr for TerrainMap {
+    type Err = RoverParseError;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        let cells = parse_map(map, |cell| match cell {
+            '.' => Some(None),
+            'r' => Some(Some(Terrain::Rock)),
+            'c' => Some(Some(Terrain::Crater)),
+            _ => None,
+        })?;
+
+        Ok(Self {
+            cells: cells.into_iter().collect(),
+            ..Self::new()
+        })
+    }
+}
+
+fn parse_map<T>(
+    map: &str,
+    parse_cell: impl Fn(char) -> Option<Option<T>>,
+) -> Result<Vec<(Coordinate, T)>, RoverParseError> {
+    let rows: Vec<&str> = map.trim_end().lines().collect();
+    let mut marked = Vec::new();
+
+    for (row, cells) in rows.iter().enumerate() {
+        let y =
+            u32::try_from(rows.len() - 1 - row).map_err(|_| RoverParseError::UnexpectedToken {
+                line: row + 1,
+                column: 1,
+                token: cells.to_string(),
+            })?;
+
+        for (column, cell) in cells.chars().enumerate() {
+            match parse_cell(cell) {
+                Some(None) => {}
+                Some(Some(value)) => {
+                    let x =
+                        u32::try_from(column).map_err(|_| RoverParseError::UnexpectedToken {
+                            line: row + 1,
+                            column: column + 1,
+                            token: cell.to_string(),
+                        })?;
+                    marked.push((Coordinate::new(x, y), value));
+                }
+                None => {
+                    return Err(RoverParseError::InvalidMapCell {
+                        line: row + 1,
+                        column: column + 1,
+                        token: cell.to_string(),
+                    })
+                }
+            }
+        }
+    }
+
+    Ok(marked)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EnergyCosts {
+    movement: u32,
+    turn: u32,
+    hold: u32,
+}
+
+impl Default for EnergyCosts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnergyCosts {
+    pub fn new() -> Self {
+        Self {
+            movement: 2,
+            turn: 1,
+            hold: 0,
+        }
+    }
+
+    pub fn with_movement(mut self, movement: u32) -> Self {
+        self.movement = movement;
+        self
+    }
+
+    pub fn with_turn(mut self, turn: u32) -> Self {
+        self.turn = turn;
+        self
+    }
+
+    pub fn with_hold(mut self, hold: u32) -> Self {
+        self.hold = hold;
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Position {
+    coordinate: Coordinate,
+    direction: Box<dyn Direction>,
+}
+
+impl Position {
+    fn new(coordinate: Coordinate, direction: Box<dyn Direction>) -> Self {
+        Self {
+            coordinate,
+            direction,
+        }
+    }
+
+    pub fn coordinate(&self) -> Coordinate {
+        self.coordinate
+    }
+
+    pub fn direction(&self) -> &dyn Direction {
+        self.direction.as_ref()
+    }
+
+    fn turn_left(&mut self) {
+        self.direction = self.direction.turn_left();
+    }
+
+    fn turn_right(&mut self) {
+        self.direction = self.direction.turn_right();
+    }
+
+    fn pitch_up(&mut self) {
+        self.direction = self.direction.pitch_up();
+    }
+
+    fn pitch_down(&mut self) {
+        self.direction = self.direction.pitch_down();
+    }
+
+    fn turn_around(&mut self) {
+        for _ in 0..self.direction.compass().points() / 2 {
+            self.turn_left();
+        }
+    }
+
+    fn next_coordinate(&self, vector: DirectionMoveVector) -> Option<Coordinate> {
+        self.coordinate.apply_vector(vector)
+    }
+
+    fn move_to(&mut self, coordinate: Coordinate) {
+        self.coordinate = coordinate;
+    }
+}
+
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        self.coordinate == other.coordinate && *self.direction == *other.direction
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.coordinate, self.direction.to_string())
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Step {
+    command: char,
+    position: Position,
+    cost: u32,
+}
+
+impl Step {
+    fn new(command: char, position: Position, cost: u32) -> Self {
+        Self {
+            command,
+            position,
+            cost,
+        }
+    }
+
+    pub fn command(&self) -> char {
+        self.command
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.command, self.position)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Trace {
+    start: Position,
+    steps: Vec<Step>,
+}
+
+impl Trace {
+    fn new(start: Position) -> Self {
+        Self {
+            start,
+            steps: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, command: char, position: Position, cost: u32) {
+        self.steps.push(Step::new(command, position, cost));
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Step> {
+        self.steps.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.steps.last().map_or(0, Step::cost)
+    }
+
+    fn positions(&self) -> impl Iterator<Item = &Position> {
+        std::iter::once(&self.start).chain(self.steps.iter().map(|step| &step.position))
+    }
+
+    fn last(&self) -> &Position {
+        self.steps.last().map_or(&self.start, |step| &step.position)
+    }
+}
+
+impl<'a> IntoIterator for &'a Trace {
+    type Item = &'a Step;
+    type IntoIter = std::slice::Iter<'a, Step>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.start)?;
+
+        for step in &self.steps {
+            write!(f, "\n{}", step)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum StopReason {
+    Completed,
+    Stopped,
+    Lost,
+    Blocked,
+    Collided,
+    Depleted,
+    Invalid(RoverParseError),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExecutionReport {
+    position: Position,
+    executed: usize,
+    skipped: usize,
+    stop_reason: StopReason,
+}
+
+impl ExecutionReport {
+    fn new(position: Position, executed: usize, skipped: usize, stop_reason: StopReason) -> Self {
+        Self {
+            position,
+            executed,
+            skipped,
+            stop_reason,
+        }
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn executed(&self) -> usize {
+        self.executed
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn stop_reason(&self) -> &StopReason {
+        &self.stop_reason
+    }
+}
+
+impl fmt::Display for ExecutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.stop_reason {
+            StopReason::Lost => write!(f, "{} LOST", self.position),
+            StopReason::Blocked => write!(f, "O:{}", self.position),
+            StopReason::Depleted => write!(f, "{} DEPLETED", self.position),
+            StopReason::Invalid(error) => write!(f, "{}", error),
+            _ => write!(f, "{}", self.position),
+        }
+    }
+}
+
+type Deployment = (Commands, Position);
+
+struct Tokens<'a> {
+    line: usize,
+    text: &'a str,
+    tokens: Vec<(usize, &'a str)>,
+    next: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: usize, text: &'a str) -> Self {
+        let tokens = text
+            .split_whitespace()
+            .map(|token| (Self::column_of(text, token), token))
+            .collect();
+
+        Self {
+            line,
+            text,
+            tokens,
+            next: 0,
+        }
+    }
+
+    fn column_of(text: &str, token: &str) -> usize {
+        let offset = token.as_ptr() as usize - text.as_ptr() as usize;
+        text[..offset].chars().count() + 1
+    }
+
+    fn next(&mut self, expected: &'static str) -> Result<(usize, &'a str), RoverParseError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .copied()
+            .ok_or(RoverParseError::MissingToken {
+                line: self.line,
+                column: self.text.chars().count() + 1,
+                expected,
+            })?;
+        self.next += 1;
+
+        Ok(token)
+    }
+
+    fn has_next(&self) -> bool {
+        self.next < self.tokens.len()
+    }
+
+    fn end(&self) -> Result<(), RoverParseError> {
+        match self.tokens.get(self.next) {
+            Some((column, token)) => Err(RoverParseError::UnexpectedToken {
+                line: self.line,
+                column: *column,
+                token: token.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn coordinate(&mut self, expected: &'static str) -> Result<u32, RoverParseError> {
+        let (column, token) = self.next(expected)?;
+
+        token
+            .parse()
+            .map_err(|_| RoverParseError::InvalidCoordinate {
+                line: self.line,
+                column,
+                token: token.to_string(),
+            })
+    }
+}
+
+const DEFAULT_EXPANSION_LIMIT: usize = 10_000;
+
+const MAX_NESTING_DEPTH: usize = 64;
+
+type ExpandedCommands = Vec<(usize, usize, char)>;
+
+type NumberedLine<'a> = (usize, &'a str);
+
+struct Definition {
+    name: String,
+    line: usize,
+    column: usize,
+    body: String,
+    body_column: usize,
+}
+
+#[derive(Default)]
+struct Definitions {
+    definitions: Vec<Definition>,
+}
+
+impl Definitions {
+    fn add(&mut self, definition: Definition) -> Result<(), RoverParseError> {
+        if self.definitions.iter().any(|d| d.name == definition.name) {
+            return Err(RoverParseError::DuplicateDefinition {
+                line: definition.line,
+                column: definition.column,
+                token: definition.name,
+            });
+        }
+
+        self.definitions.push(definition);
+        Ok(())
+    }
+
+    fn name_at(&self, chars: &[(usize, char)], index: usize) -> Option<&Definition> {
+        self.definitions
+            .iter()
+            .filter(|definition| {
+                let name: Vec<char> = definition.name.chars().collect();
+
+                chars.len() >= index + name.len()
+                    && chars[index..index + name.len()]
+                        .iter()
+                        .zip(name.iter().enumerate())
+                        .all(|((column, c), (offset, n))| {
+                            c == n && *column == chars[index].0 + offset
+                        })
+            })
+            .max_by_key(|definition| definition.name.len())
+    }
+
+    fn references(&self, definition: &Definition) -> Vec<&Definition> {
+        let chars = CommandExpander::chars(&definition.body, definition.body_column);
+        let mut references = Vec::new();
+        let mut index = 0;
+
+        while index < chars.len() {
+            match self.name_at(&chars, index) {
+                Some(reference) => {
+                    references.push(reference);
+                    index += reference.name.chars().count();
+                }
+                None => index += 1,
+            }
+        }
+
+        references
+    }
+
+    fn check_recursion(&self) -> Result<(), RoverParseError> {
+        let mut checked = HashSet::new();
+
+        for definition in &self.definitions {
+            self.visit(definition, &mut Vec::new(), &mut checked)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit<'a>(
+        &'a self,
+        definition: &'a Definition,
+        path: &mut Vec<&'a Definition>,
+        checked: &mut HashSet<&'a str>,
+    ) -> Result<(), RoverParseError> {
+        if checked.contains(definition.name.as_str()) {
+            return Ok(());
+        }
+
+        if let Some(start) = path.iter().position(|d| d.name == definition.name) {
+            let cycle: Vec<&str> = path[start..]
+                .iter()
+                .map(|d| d.name.as_str())
+                .chain([definition.name.as_str()])
+                .collect();
+
+            return Err(RoverParseError::RecursiveDefinition {
+                line: path[start].line,
+                column: path[start].column,
+                token: cycle.join(" -> "),
+            });
+        }
+
+        if path.len() >= MAX_NESTING_DEPTH {
+            return Err(RoverParseError::NestingTooDeep {
+                line: definition.line,
+                column: definition.column,
+                token: definition.name.clone(),
+                limit: MAX_NESTING_DEPTH,
+            });
+        }
+
+        path.push(definition);
+        for reference in self.references(definition) {
+            self.visit(reference, path, checked)?;
+        }
+        path.pop();
+        checked.insert(&definition.name);
+
+        Ok(())
+    }
+}
+
+struct CommandExpander<'a> {
+    line: usize,
+    chars: Vec<(usize, char)>,
+    next: usize,
+    limit: usize,
+    depth: usize,
+    definitions: &'a Definitions,
+}
+
+impl<'a> CommandExpander<'a> {
+    fn new(
+        line: usize,
+        raw_commands: &str,
+        first_column: usize,
+        limit: usize,
+        definitions: &'a Definitions,
+    ) -> Self {
+        Self {
+            line,
+            chars: Self::chars(raw_commands, first_column),
+            next: 0,
+            limit,
+            depth: 0,
+            definitions,
+        }
+    }
+
+    fn chars(raw_commands: &str, first_column: usize) -> Vec<(usize, char)> {
+        raw_commands
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(index, c)| (index + first_column, c))
+            .collect()
+    }
+
+    fn expand(mut self) -> Result<ExpandedCommands, RoverParseError> {
+        self.expand_group(None)
+    }
+
+    fn expand_group(
+        &mut self,
+        opened_at: Option<usize>,
+    ) -> Result<ExpandedCommands, RoverParseError> {
+        let mut expanded = Vec::new();
+
+        while let Some(&(column, c)) = self.chars.get(self.next) {
+            if c == ')' {
+                self.next += 1;
+
+                return match opened_at {
+                    Some(_) => Ok(expanded),
+                    None => Err(self.unbalanced(column, c)),
+                };
+            }
+
+            let (column, item) = self.expand_item()?;
+            if expanded.len() + item.len() > self.limit {
+                return Err(self.limit_exceeded(column));
+            }
+            expanded.extend(item);
+        }
+
+        match opened_at {
+            Some(column) => Err(self.unbalanced(column, '(')),
+            None => Ok(expanded),
+        }
+    }
+
+    fn expand_item(&mut self) -> Result<(usize, ExpandedCommands), RoverParseError> {
+        let (count_column, count) = self.count();
+        let (column, c) = match self.chars.get(self.next) {
+            Some(&(_, ')')) | None => {
+                return Err(RoverParseError::MissingToken {
+                    line: self.line,
+                    column: self
+                        .chars
+                        .get(self.next - 1)
+                        .map_or(count_column, |c| c.0 + 1),
+                    expected: "command after repeat count",
+                })
+            }
+            Some(&item) => item,
+        };
+
+        let body = match self.definitions.name_at(&self.chars, self.next) {
+            Some(definition) => {
+                self.nest(column, &definition.name)?;
+                self.next += definition.name.chars().count();
+                self.expand_definition(definition)?
+            }
+            None => {
+                self.next += 1;
+
+                match c {
+                    '(' => {
+                        self.nest(column, "(")?;
+                        self.depth += 1;
+                        let group = self.expand_group(Some(column))?;
+                        self.depth -= 1;
+                        group
+                    }
+                    _ => vec![(self.line, column, c)],
+                }
+            }
+        };
+
+        match body.len().checked_mul(count) {
+            Some(length) if length <= self.limit => Ok((count_column, body.repeat(count))),
+            _ => Err(self.limit_exceeded(count_column)),
+        }
+    }
+
+    fn expand_definition(
+        &self,
+        definition: &Definition,
+    ) -> Result<ExpandedCommands, RoverParseError> {
+        CommandExpander {
+            depth: self.depth + 1,
+            ..CommandExpander::new(
+                definition.line,
+                &definition.body,
+                definition.body_column,
+                self.limit,
+                self.definitions,
+            )
+        }
+        .expand()
+    }
+
+    fn nest(&self, column: usize, token: &str) -> Result<(), RoverParseError> {
+        match self.depth < MAX_NESTING_DEPTH {
+            true => Ok(()),
+            false => Err(RoverParseError::NestingTooDeep {
+                line: self.line,
+                column,
+                token: token.to_string(),
+                limit: MAX_NESTING_DEPTH,
+            }),
+        }
+    }
+
+    fn count(&mut self) -> (usize, usize) {
+        let column = self.chars.get(self.next).map_or(0, |c| c.0);
+        let mut count: Option<usize> = None;
+
+        while let Some(digit) = self.chars.get(self.next).and_then(|c| c.1.to_digit(10)) {
+            count = count
+                .unwrap_or(0)
+                .checked_mul(10)
+                .and_then(|count| count.checked_add(digit as usize))
+                .or(Some(usize::MAX));
+            self.next += 1;
+        }
+
+        (column, count.unwrap_or(1))
+    }
+
+    fn unbalanced(&self, column: usize, c: char) -> RoverParseError {
+        RoverParseError::UnbalancedParenthesis {
+            line: self.line,
+            column,
+            token: c.to_string(),
+        }
+    }
+
+    fn limit_exceeded(&self, column: usize) -> RoverParseError {
+        let item: Vec<char> = self
+            .chars
+            .iter()
+            .skip_while(|c| c.0 < column)
+            .map(|c| c.1)
+            .collect();
+        let digits = item.iter().take_while(|c| c.is_ascii_digit()).count();
+
+        RoverParseError::ExpansionLimitExceeded {
+            line: self.line,
+            column,
+            token: item[..digits.max(1)].iter().collect(),
+            limit: self.limit,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Parser {
+    expansion_limit: usize,
+    compass: Compass,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Parser {
+            expansion_limit: DEFAULT_EXPANSION_LIMIT,
+            compass: Compass::FourWay,
+        }
+    }
+
+    pub fn with_expansion_limit(mut self, expansion_limit: usize) -> Self {
+        self.expansion_limit = expansion_limit;
+        self
+    }
+
+    pub fn with_compass(mut self, compass: Compass) -> Self {
+        self.compass = compass;
+        self
+    }
+
+    fn parse(
+        &self,
+        instructions: String,
+        obstacles: &ObstacleMap,
+    ) -> Result<(Vec<Deployment>, Plateau), RoverParseError> {
+        let (definition_lines, lines): (Vec<NumberedLine>, Vec<NumberedLine>) = instructions
+            .trim_end()
+            .lines()
+            .enumerate()
+            .map(|(index, text)| (index + 1, text))
+            .partition(|(_, text)| Self::is_definition(text));
+        let definitions = self.parse_definitions(&definition_lines)?;
+        let (plateau_line, plateau) = lines.first().ok_or(RoverParseError::MissingLine {
+            line: 1,
+            expected: "plateau size",
+        })?;
+        let (plateau_line, plateau_text) = (*plateau_line, *plateau);
+        let plateau = self.parse_plateau(plateau_line, plateau_text)?;
+        if let (false, Some((columns, rows))) = (obstacles.fits(&plateau), obstacles.size) {
+            return Err(RoverParseError::MapSizeMismatch {
+                line: plateau_line,
+                column: Tokens::column_of(plateau_text, plateau_text.trim_start()),
+                token: plateau_text.trim().to_string(),
+                columns,
+                rows,
+            });
+        }
+        let rovers = lines[1..]
+            .chunks(2)
+            .map(|rover| self.parse_rover(&plateau, obstacles, &definitions, rover))
+            .collect::<Result<_, _>>()?;
+
+        Ok((rovers, plateau))
+    }
+
+    fn is_definition(line: &str) -> bool {
+        line.split_whitespace().next() == Some("def")
+    }
+
+    fn parse_definitions(&self, lines: &[NumberedLine]) -> Result<Definitions, RoverParseError> {
+        let mut definitions = Definitions::default();
+
+        for (line, text) in lines {
+            definitions.add(self.parse_definition(*line, text)?)?;
+        }
+        definitions.check_recursion()?;
+
+        Ok(definitions)
+    }
+
+    fn parse_definition(&self, line: usize, text: &str) -> Result<Definition, RoverParseError> {
+        let declaration = &text[text.find("def").unwrap_or(0) + "def".len()..];
+        let equals = declaration.find('=').ok_or(RoverParseError::MissingToken {
+            line,
+            column: text.chars().count() + 1,
+            expected: "'=' after definition name",
+        })?;
+        let name = declaration[..equals].trim();
+        let body = &declaration[equals + 1..];
+
+        if name.is_empty() {
+            return Err(RoverParseError::MissingToken {
+                line,
+                column: Tokens::column_of(text, body) - 1,
+                expected: "definition name",
+            });
+        }
+
+        let column = Tokens::column_of(text, name);
+        if !Self::is_definition_name(name) {
+            return Err(RoverParseError::InvalidDefinition {
+                line,
+                column,
+                token: name.to_string(),
+            });
+        }
+
+        Ok(Definition {
+            name: name.to_string(),
+            line,
+            column,
+            body: body.to_string(),
+            body_column: Tokens::column_of(text, body),
+        })
+    }
+
+    fn is_definition_name(name: &str) -> bool {
+        let mut chars = name.chars();
+
+        name.chars().count() >= 2
+            && chars
+                .next()
+                .is_some_and(|c| c.is_ascii_uppercase() || c == '_')
+            && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            && !name
+                .chars()
+                .all(|c| c.is_ascii_digit() || Commands::create_command(&c).is_some())
+    }
+
+    fn parse_rover(
+        &self,
+        plateau: &Plateau,
+        obstacles: &ObstacleMap,
+        definitions: &Definitions,
+        lines: &[NumberedLine],
+    ) -> Result<Deployment, RoverParseError> {
+        let (line, text) = lines[0];
+        let position = self.parse_position(plateau, line, text)?;
+
+        if obstacles.is_blocked(&position.coordinate) {
+            return Err(RoverParseError::OnObstacle {
+                line,
+                column: Tokens::column_of(text, text.trim_start()),
+                token: position.coordinate.to_string(),
+            });
+        }
+
+        if lines.len() < 2 {
+            return Ok((Commands::new(), position));
+        }
+
+        let (line, commands) = lines[1];
+        let commands = self.parse_commands(plateau, definitions, line, commands)?;
+
+        Ok((commands, position))
+    }
+
+    fn parse_plateau(&self, line: usize, plateau: &str) -> Result<Plateau, RoverParseError> {
+        let mut tokens = Tokens::new(line, plateau);
+        let width = tokens.coordinate("plateau width")?;
+        let height = tokens.coordinate("plateau height")?;
+        let altitude = match tokens.has_next() {
+            true => Some(tokens.coordinate("plateau altitude")?),
+            false => None,
+        };
+        tokens.end()?;
+
+        Ok(Plateau::new(width, height).with_altitude(altitude))
+    }
+
+    fn parse_position(
+        &self,
+        plateau: &Plateau,
+        line: usize,
+        position: &str,
+    ) -> Result<Position, RoverParseError> {
+        let mut tokens = Tokens::new(line, position);
+        let x = tokens.coordinate("x coordinate")?;
+        let y = tokens.coordinate("y coordinate")?;
+        let z = match plateau.altitude {
+            Some(_) => Some(tokens.coordinate("altitude")?),
+            None => None,
+        };
+        let (column, direction) = tokens.next("direction")?;
+        let pitch = match plateau.altitude {
+            Some(_) if tokens.has_next() => Some(tokens.next("pitch")?),
+            _ => None,
+        };
+        tokens.end()?;
+
+        let coordinate = Coordinate::new(x, y).with_altitude(z);
+        if !plateau.contains(&coordinate) {
+            return Err(RoverParseError::OutsidePlateau {
+                line,
+                column: Tokens::column_of(position, position.trim_start()),
+                token: coordinate.to_string(),
+            });
+        }
+
+        let direction = DirectionFactory::create(self.compass, direction).ok_or(
+            RoverParseError::InvalidDirection {
+                line,
+                column,
+                token: direction.to_string(),
+            },
+        )?;
+        let direction = match pitch {
+            Some((_, "UP")) => direction.pitch_up(),
+            Some((_, "DOWN")) => direction.pitch_down(),
+            Some((column, pitch)) => {
+                return Err(RoverParseError::InvalidDirection {
+                    line,
+                    column,
+                    token: pitch.to_string(),
+                })
+            }
+            None => direction,
+        };
+
+        Ok(Position::new(coordinate, direction))
+    }
+
+    fn parse_commands(
+        &self,
+        plateau: &Plateau,
+        definitions: &Definitions,
+        line: usize,
+        raw_commands: &str,
+    ) -> Result<Commands, RoverParseError> {
+        let expanded =
+            CommandExpander::new(line, raw_commands, 1, self.expansion_limit, definitions)
+                .expand()?;
+        let mut commands = Commands::new();
+
+        for (line, column, c) in expanded {
+            let command = Commands::create_command(&c)
+                .filter(|_| plateau.altitude.is_some() || !matches!(c, 'C' | 'D'))
+                .ok_or(RoverParseError::InvalidCommand {
+                    line,
+                    column,
+                    token: c.to_string(),
+                })?;
+            commands.add(command);
+        }
+
+        Ok(commands)
+    }
+}
+
+pub struct Rover {
+    parser: Parser,
+    position: Position,
+    plateau: Option<Plateau>,
+    edge_policy: EdgePolicy,
+    obstacles: ObstacleMap,
+    terrain: TerrainMap,
+    collision_policy: CollisionPolicy,
+    other_rovers: HashMap<Coordinate, usize>,
+    collisions: Vec<(usize, Coordinate)>,
+    trace: Trace,
+    energy_costs: EnergyCosts,
+    capacity: Option<u32>,
+    charge: Option<u32>,
+    spent: u32,
+    refused: usize,
+    status: Status,
+}
+
+impl Rover {
+    pub fn new(parser: Parser) -> Self {
+        Rover {
+            position: Position::new(Coordinate::new(0, 0), Box::new(North)),
+            plateau: None,
+            edge_policy: EdgePolicy::Reject,
+            obstacles: ObstacleMap::default(),
+            terrain: TerrainMap::default(),
+            collision_policy: CollisionPolicy::Ignore,
+            other_rovers: HashMap::new(),
+            collisions: Vec::new(),
+            trace: Trace::new(Position::new(Coordinate::new(0, 0), Box::new(North))),
+            energy_costs: EnergyCosts::new(),
+            capacity: None,
+            charge: None,
+            spent: 0,
+            refused: 0,
+            status: Status::Active,
+            parser,
+        }
+    }
+
+    pub fn with_edge_policy(mut self, edge_policy: EdgePolicy) -> Self {
+        self.edge_policy = edge_policy;
+        self
+    }
+
+    pub fn with_obstacles(mut self, obstacles: ObstacleMap) -> Self {
+        self.obstacles = obstacles;
+        self
+    }
+
+    pub fn with_terrain(mut self, terrain: TerrainMap) -> Self {
+        self.terrain = terrain;
+        self
+    }
+
+    pub fn with_battery(mut self, capacity: u32) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn with_energy_costs(mut self, energy_costs: EnergyCosts) -> Self {
+        self.energy_costs = energy_costs;
+        self
+    }
+
+    pub fn charge(&self) -> Option<u32> {
+        self.charge
+    }
+
+    pub fn execute(&mut self, instructions: String) -> ExecutionReport {
+        self.try_execute(instructions).unwrap_or_else(|error| {
+            ExecutionReport::new(self.position.clone(), 0, 0, StopReason::Invalid(error))
+        })
+    }
+
+    pub fn try_execute(
+        &mut self,
+        instructions: String,
+    ) -> Result<ExecutionReport, RoverParseError> {
+        let commands = self.deploy(instructions)?;
+
+        Ok(self.run(&commands))
+    }
+
+    pub fn try_trace(&mut self, instructions: String) -> Result<Trace, RoverParseError> {
+        self.try_execute(instructions)?;
+
+        Ok(self.trace.clone())
+    }
+
+    fn deploy(&mut self, instructions: String) -> Result<Commands, RoverParseError> {
+        let (mut rovers, plateau) = self.parser.parse(instructions, &self.obstacles)?;
+
+        if rovers.is_empty() {
+            return Err(RoverParseError::MissingLine {
+                line: 2,
+                expected: "rover position",
+            });
+        }
+
+        let (commands, starting_position) = rovers.remove(0);
+        self.update_plateau(plateau);
+        self.update_position(starting_position);
+
+        Ok(commands)
+    }
+
+    fn run(&mut self, commands: &Commands) -> ExecutionReport {
+        self.trace = Trace::new(self.position.clone());
+        self.charge = self.capacity;
+        self.spent = 0;
+        self.refused = 0;
+
+        if let Some(other) = self.other_rovers.get(&self.position.coordinate) {
+            self.collisions.push((*other, self.position.coordinate));
+            self.status = Status::Collided;
+        }
+
+        commands.execute(self);
+
+        let stop_reason = match self.status {
+            Status::Active => StopReason::Completed,
+            Status::Stopped => StopReason::Stopped,
+            Status::Lost => StopReason::Lost,
+            Status::Blocked => StopReason::Blocked,
+            Status::Collided => StopReason::Collided,
+            Status::Depleted => StopReason::Depleted,
+        };
+        let executed = self.trace.len() - self.refused;
+
+        ExecutionReport::new(
+            self.position.clone(),
+            executed,
+            commands.len() - executed,
+            stop_reason,
+        )
+    }
+
+    fn update_plateau(&mut self, plateau: Plateau) {
+        self.plateau = Some(plateau);
+        self.status = Status::Active;
+    }
+
+    fn update_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn is_active(&self) -> bool {
+        self.status == Status::Active
+    }
+
+    fn consume(&mut self, energy: u32) {
+        match self.charge {
+            Some(charge) if charge < energy => self.status = Status::Depleted,
+            Some(charge) => {
+                self.charge = Some(charge - energy);
+                self.spent = self.spent.saturating_add(energy);
+            }
+            None => self.spent = self.spent.saturating_add(energy),
+        }
+    }
+
+    fn move_energy(&self, vector: DirectionMoveVector) -> u32 {
+        let terrain_cost = self
+            .next_coordinate(vector)
+            .and_then(|coordinate| self.terrain.cost(&coordinate))
+            .unwrap_or(1);
+
+        self.energy_costs.movement.saturating_mul(terrain_cost)
+    }
+
+    fn record(&mut self, command: char) {
+        self.trace
+            .record(command, self.position.clone(), self.spent);
+    }
+
+    fn turn_left(&mut self) {
+        self.position.turn_left();
+    }
+
+    fn turn_right(&mut self) {
+        self.position.turn_right();
+    }
+
+    fn u_turn(&mut self) {
+        self.position.turn_around();
+    }
+
+    fn climb(&mut self) {
+        self.position.pitch_up();
+    }
+
+    fn descend(&mut self) {
+        self.position.pitch_down();
+    }
+
+    fn move_forward(&mut self) {
+        self.move_by(self.position.direction.move_vector());
+    }
+
+    fn move_backward(&mut self) {
+        self.move_by(-self.position.direction.move_vector());
+    }
+
+    fn move_by(&mut self, vector: DirectionMoveVector) {
+        match self.next_coordinate(vector) {
+            Some(coordinate)
+                if self.obstacles.is_blocked(&coordinate)
+                    || !self.terrain.is_passable(&coordinate) =>
+            {
+                self.refused += 1;
+                self.status = Status::Blocked
+            }
+            Some(coordinate) if self.other_rovers.contains_key(&coordinate) => {
+                self.collide(coordinate)
+            }
+            Some(coordinate) => self.position.move_to(coordinate),
+            None => self.reach_edge(),
+        }
+    }
+
+    fn next_coordinate(&self, vector: DirectionMoveVector) -> Option<Coordinate> {
+        match self.edge_policy {
+            EdgePolicy::Wrap => self
+                .plateau
+                .map(|plateau| plateau.wrap(&self.position.coordinate, vector)),
+            _ => self
+                .position
+                .next_coordinate(vector)
+                .filter(|c| self.plateau.is_some_and(|plateau| plateau.contains(c))),
+        }
+    }
+
+    fn collide(&mut self, coordinate: Coordinate) {
+        self.refused += 1;
+        self.collisions
+            .push((self.other_rovers[&coordinate], coordinate));
+
+        if self.collision_policy == CollisionPolicy::Abort {
+            self.status = Status::Collided;
+        }
+    }
+
+    fn reach_edge(&mut self) {
+        self.refused += 1;
+        match self.edge_policy {
+            EdgePolicy::Stop => self.status = Status::Stopped,
+            EdgePolicy::Reject | EdgePolicy::Wrap => {}
+            EdgePolicy::Lost => self.status = Status::Lost,
+        }
+    }
+}
+
+struct Fleet {
+    rovers: Vec<(Rover, Commands)>,
+    collision_policy: CollisionPolicy,
+    collisions: Vec<Collision>,
+}
+
+impl Fleet {
+    fn new(collision_policy: CollisionPolicy) -> Self {
+        Self {
+            rovers: Vec::new(),
+            collision_policy,
+            collisions: Vec::new(),
+        }
+    }
+
+    fn land(&mut self, mut rover: Rover, commands: Commands) {
+        rover.collision_policy = self.collision_policy;
+        self.rovers.push((rover, commands));
+    }
+
+    fn run(&mut self) -> Vec<String> {
+        let mut positions = Vec::new();
+
+        for index in 0..self.rovers.len() {
+            if self.collision_policy != CollisionPolicy::Ignore {
+                self.rovers[index].0.other_rovers = self.other_rovers(index);
+            }
+
+            let (rover, commands) = &mut self.rovers[index];
+            positions.push(rover.run(commands).to_string());
+            self.collisions.extend(
+                rover
+                    .collisions
+                    .drain(..)
+                    .map(|(other, coordinate)| Collision::new(index + 1, other, coordinate)),
+            );
+        }
+
+        positions
+    }
+
+    fn other_rovers(&self, index: usize) -> HashMap<Coordinate, usize> {
+        self.rovers
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .map(|(other, (rover, _))| (rover.position.coordinate, other + 1))
+            .collect()
+    }
+}
+
+pub struct Mission {
+    parser: Parser,
+    edge_policy: EdgePolicy,
+    obstacles: ObstacleMap,
+    terrain: TerrainMap,
+    collision_policy: CollisionPolicy,
+    collisions: Vec<Collision>,
+    energy_costs: EnergyCosts,
+    capacity: Option<u32>,
+    plateau: Option<Plateau>,
+    traces: Vec<Trace>,
+    charges: Vec<Option<u32>>,
+}
+
+impl Mission {
+    pub fn new(parser: Parser) -> Self {
+        Mission {
+            parser,
+            edge_policy: EdgePolicy::Reject,
+            obstacles: ObstacleMap::default(),
+            terrain: TerrainMap::default(),
+            collision_policy: CollisionPolicy::Ignore,
+            collisions: Vec::new(),
+            energy_costs: EnergyCosts::new(),
+            capacity: None,
+            plateau: None,
+            traces: Vec::new(),
+            charges: Vec::new(),
+        }
+    }
+
+    pub fn with_edge_policy(mut self, edge_policy: EdgePolicy) -> Self {
+        self.edge_policy = edge_policy;
+        self
+    }
+
+    pub fn with_obstacles(mut self, obstacles: ObstacleMap) -> Self {
+        self.obstacles = obstacles;
+        self
+    }
+
+    pub fn with_terrain(mut self, terrain: TerrainMap) -> Self {
+        self.terrain = terrain;
+        self
+    }
+
+    pub fn with_collision_policy(mut self, collision_policy: CollisionPolicy) -> Self {
+        self.collision_policy = collision_policy;
+        self
+    }
+
+    pub fn with_battery(mut self, capacity: u32) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn with_energy_costs(mut self, energy_costs: EnergyCosts) -> Self {
+        self.energy_costs = energy_costs;
+        self
+    }
+
+    pub fn collisions(&self) -> &[Collision] {
+        &self.collisions
+    }
+
+    pub fn charges(&self) -> &[Option<u32>] {
+        &self.charges
+    }
+
+    pub fn execute(&mut self, instructions: String) -> Vec<String> {
+        self.try_execute(instructions)
+            .unwrap_or_else(|error| vec![error.to_string()])
+    }
+
+    pub fn try_execute(&mut self, instructions: String) -> Result<Vec<String>, RoverParseError> {
+        let (rovers, plateau) = self.parser.parse(instructions, &self.obstacles)?;
+        let mut fleet = Fleet::new(self.collision_policy);
+
+        for (commands, starting_position) in rovers {
+            let mut rover = Rover::new(Parser::new())
+                .with_edge_policy(self.edge_policy)
+                .with_obstacles(self.obstacles.clone())
+                .with_terrain(self.terrain.clone())
+                .with_energy_costs(self.energy_costs);
+            rover.capacity = self.capacity;
+            rover.update_plateau(plateau);
+            rover.update_position(starting_position);
+            fleet.land(rover, commands);
+        }
+
+        let positions = fleet.run();
+        self.collisions = std::mem::take(&mut fleet.collisions);
+        self.plateau = Some(plateau);
+        (self.traces, self.charges) = fleet
+            .rovers
+            .into_iter()
+            .map(|(rover, _)| (rover.trace, rover.charge))
+            .unzip();
+
+        Ok(positions)
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_unit_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn stays_in_same_position_when_no_commands_are_sent() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\n")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn turn_left_turns_from_north_to_west() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nL")).to_string();
+
+        assert_eq!(String::from("1 1 W"), position);
+    }
+
+    #[test]
+    fn turn_left_turns_from_west_to_south() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 W\nL")).to_string();
+
+        assert_eq!(String::from("1 1 S"), position);
+    }
+
+    #[test]
+    fn turn_left_turns_from_south_to_east() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 S\nL")).to_string();
+
+        assert_eq!(String::from("1 1 E"), position);
+    }
+
+    #[test]
+    fn turn_left_turns_from_east_to_north() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 E\nL")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn turn_left_four_time_ends_in_same_position() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nLLLL")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn turn_right_turns_from_north_to_east() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nR")).to_string();
+
+        assert_eq!(String::from("1 1 E"), position);
+    }
+
+    #[test]
+    fn turn_right_turns_from_east_to_south() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 E\nR")).to_string();
+
+        assert_eq!(String::from("1 1 S"), position);
+    }
+
+    #[test]
+    fn turn_right_turns_from_south_to_west() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 S\nR")).to_string();
+
+        assert_eq!(String::from("1 1 W"), position);
+    }
+
+    #[test]
+    fn turn_right_turns_from_west_to_north() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 W\nR")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn turn_right_four_time_ends_in_same_position() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nRRRR")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn move_north() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nM")).to_string();
+
+        assert_eq!(String::from("1 2 N"), position);
+    }
+
+    #[test]
+    fn move_south() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 S\nM")).to_string();
+
+        assert_eq!(String::from("1 0 S"), position);
+    }
+
+    #[test]
+    fn move_west() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 W\nM")).to_string();
+
+        assert_eq!(String::from("0 1 W"), position);
+    }
+
+    #[test]
+    fn move_east() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 E\nM")).to_string();
+
+        assert_eq!(String::from("2 1 E"), position);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_acceptance_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn turn_left_and_move() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("5 5\n1 2 N\nLMLMLMLMM"))
+            .to_string();
+
+        assert_eq!(String::from("1 3 N"), position);
+    }
+
+    #[test]
+    fn turn_right_and_move() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("5 5\n3 3 E\nMMRMMRMRRM"))
+            .to_string();
+
+        assert_eq!(String::from("5 1 E"), position);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_plateau_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("5 5\n1 5 N\nMR", "1 5 E" ; "north edge")]
+    #[test_case("5 5\n1 0 S\nMR", "1 0 W" ; "south edge")]
+    #[test_case("5 5\n5 1 E\nMR", "5 1 S" ; "east edge")]
+    #[test_case("5 5\n0 1 W\nMR", "0 1 N" ; "west edge")]
+    fn reject_policy_skips_the_move_and_keeps_executing(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Reject);
+
+        let position = rover.execute(String::from(instructions)).to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test_case("5 5\n1 5 N\nMR", "1 5 N" ; "north edge")]
+    #[test_case("5 5\n1 0 S\nMR", "1 0 S" ; "south edge")]
+    #[test_case("5 5\n5 1 E\nMR", "5 1 E" ; "east edge")]
+    #[test_case("5 5\n0 1 W\nMR", "0 1 W" ; "west edge")]
+    fn stop_policy_halts_the_rover_at_the_edge(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Stop);
+
+        let position = rover.execute(String::from(instructions)).to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test_case("5 5\n1 5 N\nMR", "1 5 N LOST" ; "north edge")]
+    #[test_case("5 5\n1 0 S\nMR", "1 0 S LOST" ; "south edge")]
+    #[test_case("5 5\n5 1 E\nMR", "5 1 E LOST" ; "east edge")]
+    #[test_case("5 5\n0 1 W\nMR", "0 1 W LOST" ; "west edge")]
+    fn lost_policy_reports_the_rover_as_lost(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        let position = rover.execute(String::from(instructions)).to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test]
+    fn reject_is_the_default_edge_policy() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("2 2\n0 0 S\nMLMMMM"))
+            .to_string();
+
+        assert_eq!(String::from("2 0 E"), position);
+    }
+
+    #[test]
+    fn does_not_overflow_at_the_largest_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from(
+                "4294967295 4294967295\n4294967295 4294967295 N\nMRM",
+            ))
+            .to_string();
+
+        assert_eq!(String::from("4294967295 4294967295 E"), position);
+    }
+
+    #[test]
+    fn drives_beyond_the_old_eight_bit_limit() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("1000 1000\n254 300 E\nMMMLM"))
+            .to_string();
+
+        assert_eq!(String::from("257 301 N"), position);
+    }
+
+    #[test]
+    fn overflow_at_the_largest_plateau_is_reported_as_lost() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        let position = rover
+            .execute(String::from("4294967295 0\n4294967295 0 E\nM"))
+            .to_string();
+
+        assert_eq!(String::from("4294967295 0 E LOST"), position);
+    }
+
+    #[test]
+    fn lost_rover_is_active_again_on_next_execution() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        rover.execute(String::from("5 5\n0 0 S\nM"));
+        let position = rover.execute(String::from("5 5\n0 0 N\nM")).to_string();
+
+        assert_eq!(String::from("0 1 N"), position);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_mission_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn executes_the_canonical_two_rover_example() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions = mission.execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMMRMMRMRRM"));
+
+        assert_eq!(vec!["1 3 N", "5 1 E"], positions);
+    }
+
+    #[test]
+    fn reports_one_line_per_rover() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions =
+            mission.execute(String::from("5 5\n0 0 N\nM\n1 1 E\nM\n2 2 S\nM\n3 3 W\nM"));
+
+        assert_eq!(vec!["0 1 N", "2 1 E", "2 1 S", "2 3 W"], positions);
+    }
+
+    #[test]
+    fn last_rover_may_omit_its_command_line() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions = mission.execute(String::from("5 5\n1 2 N\nM\n3 3 E"));
+
+        assert_eq!(vec!["1 3 N", "3 3 E"], positions);
+    }
+
+    #[test]
+    fn rovers_share_the_plateau_and_edge_policy() {
+        let mut mission = Mission::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        let positions = mission.execute(String::from("1 1\n0 0 N\nMM\n1 1 E\nLM"));
+
+        assert_eq!(vec!["0 1 N LOST", "1 1 N LOST"], positions);
+    }
+
+    #[test]
+    fn rover_executes_only_the_first_rover_of_a_mission() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMMRMMRMRRM"))
+            .to_string();
+
+        assert_eq!(String::from("1 3 N"), position);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_parse_error_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn empty_instructions_are_missing_the_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from(""));
+
+        assert_eq!(
+            Err(RoverParseError::MissingLine {
+                line: 1,
+                expected: "plateau size"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn rover_needs_a_position_line() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n"));
+
+        assert_eq!(
+            Err(RoverParseError::MissingLine {
+                line: 2,
+                expected: "rover position"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn plateau_needs_a_height() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5\n1 1 N"));
+
+        assert_eq!(
+            Err(RoverParseError::MissingToken {
+                line: 1,
+                column: 2,
+                expected: "plateau height"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn coordinates_must_be_numbers() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 y N\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCoordinate {
+                line: 2,
+                column: 3,
+                token: String::from("y")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn coordinates_must_fit_the_coordinate_type() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 4294967296\n1 1 N"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCoordinate {
+                line: 1,
+                column: 3,
+                token: String::from("4294967296")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn direction_must_be_a_compass_point() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 1 X\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidDirection {
+                line: 2,
+                column: 5,
+                token: String::from("X")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn position_line_rejects_extra_tokens() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 1 N E\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::UnexpectedToken {
+                line: 2,
+                column: 7,
+                token: String::from("E")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn position_must_be_on_the_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n6 1 N\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::OutsidePlateau {
+                line: 2,
+                column: 1,
+                token: String::from("6 1")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn unknown_commands_are_rejected() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n1 1 N\nMMXM"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 3,
+                column: 3,
+                token: String::from("X")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_rover_that_caused_them() {
+        let mut mission = Mission::new(Parser::new());
+
+        let result = mission.try_execute(String::from("5 5\n1 2 N\nLM\n3 3 E\nMMRQ"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 5,
+                column: 4,
+                token: String::from("Q")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn execute_reports_the_error_as_text() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nMMXM")).to_string();
+
+        assert_eq!(
+            String::from("line 3, column 3: invalid command 'X'"),
+            position
+        );
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_obstacle_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    const MAP: &str = "\
+......
+..#...
+......
+.#....
+......
+......
+";
+
+    fn rover_with_map() -> Rover {
+        Rover::new(Parser::new()).with_obstacles(MAP.parse().unwrap())
+    }
+
+    #[test]
+    fn moves_freely_around_obstacles() {
+        let mut rover = rover_with_map();
+
+        let position = rover
+            .execute(String::from("5 5\n0 0 N\nMMMMMRMMMMM"))
+            .to_string();
+
+        assert_eq!(String::from("5 5 E"), position);
+    }
+
+    #[test]
+    fn stops_at_the_last_safe_position_before_an_obstacle() {
+        let mut rover = rover_with_map();
+
+        let position = rover.execute(String::from("5 5\n1 0 N\nMMMM")).to_string();
+
+        assert_eq!(String::from("O:1 1 N"), position);
+    }
+
+    #[test]
+    fn ignores_the_remaining_commands_after_hitting_an_obstacle() {
+        let mut rover = rover_with_map();
+
+        let position = rover
+            .execute(String::from("5 5\n0 4 E\nMMMRRM"))
+            .to_string();
+
+        assert_eq!(String::from("O:1 4 E"), position);
+    }
+
+    #[test]
+    fn turning_next_to_an_obstacle_is_allowed() {
+        let mut rover = rover_with_map();
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nRRLL")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn every_rover_of_a_mission_sees_the_obstacles() {
+        let mut mission = Mission::new(Parser::new()).with_obstacles(MAP.parse().unwrap());
+
+        let positions = mission.execute(String::from("5 5\n1 0 N\nMM\n3 4 W\nMMM"));
+
+        assert_eq!(vec!["O:1 1 N", "O:3 4 W"], positions);
+    }
+
+    #[test]
+    fn map_rows_are_read_from_north_to_south() {
+        let mut rover = Rover::new(Parser::new()).with_obstacles("#.\n..".parse().unwrap());
+
+        let position = rover.execute(String::from("1 1\n0 0 N\nM")).to_string();
+
+        assert_eq!(String::from("O:0 0 N"), position);
+    }
+
+    #[test]
+    fn map_rejects_unknown_cells() {
+        let map = "...\n.x.\n...".parse::<ObstacleMap>();
+
+        assert_eq!(
+            Err(RoverParseError::InvalidMapCell {
+                line: 2,
+                column: 2,
+                token: String::from("x")
+            }),
+            map.map(|_| ())
+        );
+    }
+
+    #[test]
+    fn map_must_cover_the_whole_plateau() {
+        let mut rover = Rover::new(Parser::new()).with_obstacles("#.\n..".parse().unwrap());
+
+        assert_eq!(
+            Err(RoverParseError::MapSizeMismatch {
+                line: 1,
+                column: 1,
+                token: String::from("5 5"),
+                columns: 2,
+                rows: 2,
+            }),
+            rover.try_execute(String::from("5 5\n0 5 N\nM"))
+        );
+    }
+
+    #[test]
+    fn mission_rejects_a_map_of_another_size() {
+        let mut mission = Mission::new(Parser::new()).with_obstacles(MAP.parse().unwrap());
+
+        let positions = mission.execute(String::from("4 4\n1 0 N\nMM"));
+
+        assert_eq!(
+            vec!["line 1, column 1: plateau '4 4' does not match the 6x6 obstacle map"],
+            positions
+        );
+    }
+
+    #[test]
+    fn rover_cannot_be_deployed_on_an_obstacle() {
+        let mut rover = rover_with_map();
+
+        assert_eq!(
+            Err(RoverParseError::OnObstacle {
+                line: 2,
+                column: 1,
+                token: String::from("1 2"),
+            }),
+            rover.try_execute(String::from("5 5\n1 2 N\nM"))
+        );
+    }
+
+    #[test]
+    fn mission_rejects_a_rover_deployed_on_an_obstacle() {
+        let mut mission = Mission::new(Parser::new()).with_obstacles(MAP.parse().unwrap());
+
+        let positions = mission.execute(String::from("5 5\n0 0 N\nM\n1 2 E\nM"));
+
+        assert_eq!(
+            vec!["line 4, column 1: position '1 2' is on an obstacle"],
+            positions
+        );
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_collision_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    const CROSSING_PATHS: &str = "5 5\n1 1 E\nMMM\n4 3 S\nMMM";
+
+    #[test]
+    fn ignores_other_rovers_by_default() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions = mission.execute(String::from(CROSSING_PATHS));
+
+        assert_eq!(vec!["4 1 E", "4 0 S"], positions);
+        assert!(mission.collisions().is_empty());
+    }
+
+    #[test]
+    fn reject_keeps_the_rover_out_of_the_occupied_cell_and_carries_on() {
+        let mut mission =
+            Mission::new(Parser::new()).with_collision_policy(CollisionPolicy::Reject);
+
+        let positions = mission.execute(String::from("5 5\n2 1 E\n\n0 1 E\nMMMLM"));
+
+        assert_eq!(vec!["2 1 E", "1 2 N"], positions);
+        assert_eq!(
+            vec![
+                Collision::new(2, 1, Coordinate::new(2, 1)),
+                Collision::new(2, 1, Coordinate::new(2, 1))
+            ],
+            mission.collisions()
+        );
+    }
+
+    #[test]
+    fn abort_drops_the_remaining_commands_of_the_offending_rover() {
+        let mut mission = Mission::new(Parser::new()).with_collision_policy(CollisionPolicy::Abort);
+
+        let positions = mission.execute(String::from("5 5\n2 1 E\n\n0 1 E\nMMMLM"));
+
+        assert_eq!(vec!["2 1 E", "1 1 E"], positions);
+        assert_eq!(
+            vec![Collision::new(2, 1, Coordinate::new(2, 1))],
+            mission.collisions()
+        );
+    }
+
+    #[test]
+    fn rovers_see_where_earlier_rovers_finished() {
+        let mut mission = Mission::new(Parser::new()).with_collision_policy(CollisionPolicy::Abort);
+
+        let positions = mission.execute(String::from(CROSSING_PATHS));
+
+        assert_eq!(vec!["4 1 E", "4 2 S"], positions);
+        assert_eq!(
+            vec![Collision::new(2, 1, Coordinate::new(4, 1))],
+            mission.collisions()
+        );
+    }
+
+    #[test]
+    fn rovers_see_where_later_rovers_landed() {
+        let mut mission = Mission::new(Parser::new()).with_collision_policy(CollisionPolicy::Abort);
+
+        let positions = mission.execute(String::from("5 5\n0 0 N\nMMM\n0 2 E\nM"));
+
+        assert_eq!(vec!["0 1 N", "1 2 E"], positions);
+        assert_eq!(
+            vec![Collision::new(1, 2, Coordinate::new(0, 2))],
+            mission.collisions()
+        );
+    }
+
+    #[test]
+    fn rovers_landing_on_the_same_cell_cannot_move() {
+        let mut mission =
+            Mission::new(Parser::new()).with_collision_policy(CollisionPolicy::Reject);
+
+        let positions = mission.execute(String::from("5 5\n2 2 N\nM\n2 2 E\nM"));
+
+        assert_eq!(vec!["2 2 N", "2 2 E"], positions);
+        assert_eq!(
+            vec![
+                Collision::new(1, 2, Coordinate::new(2, 2)),
+                Collision::new(2, 1, Coordinate::new(2, 2))
+            ],
+            mission.collisions()
+        );
+    }
+
+    #[test]
+    fn collision_report_names_both_rovers_and_the_cell() {
+        let collision = Collision::new(2, 1, Coordinate::new(3, 1));
+
+        assert_eq!(
+            (2, 1, (3, 1)),
+            (collision.rover(), collision.other(), collision.cell())
+        );
+        assert_eq!(
+            "rover 2 collided with rover 1 at 3 1",
+            collision.to_string()
+        );
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_wrap_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("5 5\n1 5 N\nM", "1 0 N" ; "north edge")]
+    #[test_case("5 5\n1 0 S\nM", "1 5 S" ; "south edge")]
+    #[test_case("5 5\n5 1 E\nM", "0 1 E" ; "east edge")]
+    #[test_case("5 5\n0 1 W\nM", "5 1 W" ; "west edge")]
+    fn reenters_on_the_opposite_edge(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Wrap);
+
+        let position = rover.execute(String::from(instructions)).to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test]
+    fn wraps_using_the_plateau_dimensions() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Wrap);
+
+        let position = rover
+            .execute(String::from("3 1\n0 0 E\nMMMMMMLMMM"))
+            .to_string();
+
+        assert_eq!(String::from("2 1 N"), position);
+    }
+
+    #[test]
+    fn wraps_at_the_largest_plateau() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Wrap);
+
+        let position = rover
+            .execute(String::from("4294967295 4294967295\n4294967295 0 E\nMRM"))
+            .to_string();
+
+        assert_eq!(String::from("0 4294967295 S"), position);
+    }
+
+    #[test]
+    fn obstacles_on_the_far_side_still_block() {
+        let mut rover = Rover::new(Parser::new())
+            .with_edge_policy(EdgePolicy::Wrap)
+            .with_obstacles("..\n#.".parse().unwrap());
+
+        let position = rover.execute(String::from("1 1\n1 0 E\nM")).to_string();
+
+        assert_eq!(String::from("O:1 0 E"), position);
+    }
+
+    #[test]
+    fn is_selected_per_mission() {
+        let mut wrapping = Mission::new(Parser::new()).with_edge_policy(EdgePolicy::Wrap);
+        let mut bounded = Mission::new(Parser::new());
+
+        let instructions = "2 2\n0 0 S\nM\n2 2 E\nM";
+
+        assert_eq!(
+            vec!["0 2 S", "0 2 E"],
+            wrapping.execute(String::from(instructions))
+        );
+        assert_eq!(
+            vec!["0 0 S", "2 2 E"],
+            bounded.execute(String::from(instructions))
+        );
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_extended_command_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn move_backward_north() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nB")).to_string();
+
+        assert_eq!(String::from("1 0 N"), position);
+    }
+
+    #[test]
+    fn move_backward_south() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 S\nB")).to_string();
+
+        assert_eq!(String::from("1 2 S"), position);
+    }
+
+    #[test]
+    fn move_backward_west() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 W\nB")).to_string();
+
+        assert_eq!(String::from("2 1 W"), position);
+    }
+
+    #[test]
+    fn move_backward_east() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 E\nB")).to_string();
+
+        assert_eq!(String::from("0 1 E"), position);
+    }
+
+    #[test]
+    fn u_turn_turns_from_north_to_south() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nU")).to_string();
+
+        assert_eq!(String::from("1 1 S"), position);
+    }
+
+    #[test]
+    fn u_turn_turns_from_south_to_north() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 S\nU")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn u_turn_turns_from_west_to_east() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 W\nU")).to_string();
+
+        assert_eq!(String::from("1 1 E"), position);
+    }
+
+    #[test]
+    fn u_turn_turns_from_east_to_west() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 E\nU")).to_string();
+
+        assert_eq!(String::from("1 1 W"), position);
+    }
+
+    #[test]
+    fn hold_keeps_facing_north() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nH")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn hold_keeps_facing_south() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 S\nH")).to_string();
+
+        assert_eq!(String::from("1 1 S"), position);
+    }
+
+    #[test]
+    fn hold_keeps_facing_west() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 W\nH")).to_string();
+
+        assert_eq!(String::from("1 1 W"), position);
+    }
+
+    #[test]
+    fn hold_keeps_facing_east() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 E\nH")).to_string();
+
+        assert_eq!(String::from("1 1 E"), position);
+    }
+
+    #[test]
+    fn u_turn_twice_ends_in_same_position() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover.execute(String::from("5 5\n1 1 N\nUU")).to_string();
+
+        assert_eq!(String::from("1 1 N"), position);
+    }
+
+    #[test]
+    fn move_backward_respects_the_plateau_edge() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        let position = rover.execute(String::from("5 5\n0 0 N\nB")).to_string();
+
+        assert_eq!(String::from("0 0 N LOST"), position);
+    }
+
+    #[test]
+    fn move_backward_stops_before_an_obstacle() {
+        let mut rover = Rover::new(Parser::new()).with_obstacles("...\n...\n#..".parse().unwrap());
+
+        let position = rover.execute(String::from("2 2\n0 2 N\nBB")).to_string();
+
+        assert_eq!(String::from("O:0 1 N"), position);
+    }
+
+    #[test]
+    fn backward_u_turn_and_hold_combine_with_moves() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("5 5\n2 2 N\nMMBHURMBB"))
+            .to_string();
+
+        assert_eq!(String::from("3 3 W"), position);
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_command_expansion_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("10M", "0 10 N" ; "run length")]
+    #[test_case("3(MR)", "1 0 W" ; "group")]
+    #[test_case("2(2(M)R)", "2 2 S" ; "nested groups")]
+    #[test_case("2M3(R)M", "0 2 W" ; "run length around a group")]
+    #[test_case("4(MR)", "0 0 N" ; "group returning home")]
+    #[test_case("0M", "0 0 N" ; "zero repetitions")]
+    #[test_case("1 0 M", "0 10 N" ; "whitespace inside a count")]
+    fn expands_compact_programs(commands: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(format!("10 10\n0 0 N\n{}", commands))
+            .to_string();
+
+        assert_eq!(String::from(expected), position);
+    }
+
+    #[test]
+    fn expanded_program_matches_the_long_form() {
+        let mut compact = Rover::new(Parser::new());
+        let mut long = Rover::new(Parser::new());
+
+        assert_eq!(
+            long.execute(String::from("9 9\n0 0 N\nMMMRMRMRMLMLMM"))
+                .to_string(),
+            compact
+                .execute(String::from("9 9\n0 0 N\n3MR2(MR)M2(LM)M"))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn unclosed_group_points_at_its_opening_parenthesis() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\nM2(M(R)"));
+
+        assert_eq!(
+            Err(RoverParseError::UnbalancedParenthesis {
+                line: 3,
+                column: 3,
+                token: String::from("(")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn stray_closing_parenthesis_is_rejected() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\n2(M)R)"));
+
+        assert_eq!(
+            Err(RoverParseError::UnbalancedParenthesis {
+                line: 3,
+                column: 6,
+                token: String::from(")")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn repeat_count_needs_a_command() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\nM12"));
+
+        assert_eq!(
+            Err(RoverParseError::MissingToken {
+                line: 3,
+                column: 4,
+                expected: "command after repeat count"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn invalid_commands_inside_groups_keep_their_column() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\n3(MX)"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 3,
+                column: 4,
+                token: String::from("X")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn run_length_is_bounded_by_the_expansion_limit() {
+        let mut rover = Rover::new(Parser::new().with_expansion_limit(100));
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\nM101R"));
+
+        assert_eq!(
+            Err(RoverParseError::ExpansionLimitExceeded {
+                line: 3,
+                column: 2,
+                token: String::from("101"),
+                limit: 100
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn nested_groups_are_bounded_by_the_expansion_limit() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\n1000(1000(1000(M)))"));
+
+        assert_eq!(
+            Err(RoverParseError::ExpansionLimitExceeded {
+                line: 3,
+                column: 6,
+                token: String::from("1000"),
+                limit: 10_000
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn whole_program_is_bounded_by_the_expansion_limit() {
+        let mut rover = Rover::new(Parser::new().with_expansion_limit(10));
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\n5(M)5(L)R"));
+
+        assert_eq!(
+            Err(RoverParseError::ExpansionLimitExceeded {
+                line: 3,
+                column: 9,
+                token: String::from("R"),
+                limit: 10
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn deeply_nested_groups_are_rejected() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(format!("5 5\n0 0 N\n{}", "(".repeat(200_000)));
+
+        assert_eq!(
+            Err(RoverParseError::NestingTooDeep {
+                line: 3,
+                column: 65,
+                token: String::from("("),
+                limit: 64
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn groups_may_nest_up_to_the_depth_limit() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(format!("5 5\n0 0 N\n{}M{}", "(".repeat(64), ")".repeat(64)))
+            .to_string();
+
+        assert_eq!(String::from("0 1 N"), position);
+    }
+
+    #[test]
+    fn counts_too_large_for_the_platform_are_bounded() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\n0 0 N\n99999999999999999999999M"));
+
+        assert_eq!(
+            Err(RoverParseError::ExpansionLimitExceeded {
+                line: 3,
+                column: 1,
+                token: String::from("99999999999999999999999"),
+                limit: 10_000
+            }),
+            result
+        );
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_definition_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test]
+    fn invokes_a_definition_from_the_command_line() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from("def SQUARE = MRMRMRMR\n5 5\n1 1 N\nMSQUAREM"))
+            .to_string();
+
+        assert_eq!(String::from("1 3 N"), position);
+    }
+
+    #[test]
+    fn definitions_are_shared_by_every_rover_of_a_mission() {
+        let mut mission = Mission::new(Parser::new());
+
+        let positions = mission.execute(String::from(
+            "5 5\ndef LEG = MML\n0 0 E\nLEGLEG\n5 5 W\n2(LEG)",
+        ));
+
+        assert_eq!(vec!["2 2 W", "3 3 E"], positions);
+    }
+
+    #[test]
+    fn definitions_can_use_other_definitions_and_groups() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from(
+                "def STEP = 2M\ndef STAIRS = 3(STEP R STEP L)\n9 9\n0 0 N\nSTAIRS",
+            ))
+            .to_string();
+
+        assert_eq!(String::from("6 6 N"), position);
+    }
+
+    #[test]
+    fn longest_definition_name_wins() {
+        let mut rover = Rover::new(Parser::new());
+
+        let position = rover
+            .execute(String::from(
+                "def TWO = 2M\ndef TWOTWO = R\n5 5\n0 0 N\nTWOTWO",
+            ))
+            .to_string();
+
+        assert_eq!(String::from("0 0 E"), position);
+    }
+
+    #[test]
+    fn definition_names_do_not_span_whitespace() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("def SQ = MR\n5 5\n0 0 N\nS Q"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 4,
+                column: 1,
+                token: String::from("S")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn errors_inside_a_definition_point_at_its_line() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\ndef BAD = MXM\n0 0 N\nBAD"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 2,
+                column: 12,
+                token: String::from("X")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn recursive_definition_is_rejected_naming_the_cycle() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from(
+            "def AA = M XX\ndef XX = L YY\ndef YY = AA\n5 5\n0 0 N\nM",
+        ));
+
+        assert_eq!(
+            Err(RoverParseError::RecursiveDefinition {
+                line: 1,
+                column: 5,
+                token: String::from("AA -> XX -> YY -> AA")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn self_recursive_definition_is_rejected() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("5 5\ndef LOOP = M LOOP\n0 0 N\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::RecursiveDefinition {
+                line: 2,
+                column: 5,
+                token: String::from("LOOP -> LOOP")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn duplicate_definition_is_rejected() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("def GO = M\ndef GO = MM\n5 5\n0 0 N\nGO"));
+
+        assert_eq!(
+            Err(RoverParseError::DuplicateDefinition {
+                line: 2,
+                column: 5,
+                token: String::from("GO")
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn single_letter_names_would_shadow_commands() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("def M = LL\n5 5\n0 0 N\nM"));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidDefinition {
+                line: 1,
+                column: 5,
+                token: String::from("M")
+            }),
+            result
+        );
+    }
+
+    #[test_case("LR" ; "turns")]
+    #[test_case("MMM" ; "moves")]
+    #[test_case("M2" ; "run length")]
+    #[test_case("CD" ; "pitch commands")]
+    fn names_that_read_as_commands_would_shadow_them(name: &str) {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(format!("def {} = MM\n5 5\n1 1 N\nLRM", name));
+
+        assert_eq!(
+            Err(RoverParseError::InvalidDefinition {
+                line: 1,
+                column: 5,
+                token: String::from(name)
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn definition_needs_an_equals_sign() {
+        let mut rover = Rover::new(Parser::new());
+
+        let result = rover.try_execute(String::from("def GO MM\n5 5\n0 0 N\nGO"));
+
+        assert_eq!(
+            Err(RoverParseError::MissingToken {
+                line: 1,
+                column: 10,
+                expected: "'=' after definition name"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn long_definition_chains_are_rejected() {
+        let mut rover = Rover::new(Parser::new());
+        let definitions: String = (0..200)
+            .map(|index| format!("def STEP{} = STEP{}\n", index, index + 1))
+            .collect();
+
+        let result =
+            rover.try_execute(format!("{}def STEP200 = M\n5 5\n0 0 N\nSTEP0", definitions));
+
+        assert_eq!(
+            Err(RoverParseError::NestingTooDeep {
+                line: 65,
+                column: 5,
+                token: String::from("STEP64"),
+                limit: 64
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn definitions_count_towards_the_expansion_limit() {
+        let mut rover = Rover::new(Parser::new().with_expansion_limit(20));
+
+        let result = rover.try_execute(String::from("def TEN = 10M\n5 5\n0 0 N\n3(TEN)"));
+
+        assert_eq!(
+            Err(RoverParseError::ExpansionLimitExceeded {
+                line: 4,
+                column: 1,
+                token: String::from("3"),
+                limit: 20
+            }),
+            result
+        );
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_trace_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    fn position(x: u32, y: u32, direction: &str) -> Position {
+        Position::new(
+            Coordinate::new(x, y),
+            DirectionFactory::create(Compass::FourWay, direction).unwrap(),
+        )
+    }
+
+    #[test]
+    fn records_every_intermediate_position() {
+        let mut rover = Rover::new(Parser::new());
+
+        let trace = rover.try_trace(String::from("5 5\n1 2 N\nLMR")).unwrap();
+
+        let mut expected = Trace::new(position(1, 2, "N"));
+        expected.record('L', position(1, 2, "W"), 1);
+        expected.record('M', position(0, 2, "W"), 3);
+        expected.record('R', position(0, 2, "N"), 4);
+        assert_eq!(expected, trace);
+    }
+
+    #[test]
+    fn prints_one_line_per_step() {
+        let mut rover = Rover::new(Parser::new());
+
+        let trace = rover.try_trace(String::from("5 5\n1 2 N\nLMLM")).unwrap();
+
+        assert_eq!(
+            "1 2 N\nL 1 2 W\nM 0 2 W\nL 0 2 S\nM 0 1 S",
+            trace.to_string()
+        );
+    }
+
+    #[test]
+    fn iterates_over_the_commands_that_produced_each_step() {
+        let mut rover = Rover::new(Parser::new());
+
+        let trace = rover
+            .try_trace(String::from("5 5\n1 2 N\n2(MR)BUH"))
+            .unwrap();
+
+        assert_eq!(
+            vec!['M', 'R', 'M', 'R', 'B', 'U', 'H'],
+            trace.iter().map(Step::command).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn exposes_the_position_after_each_step() {
+        let mut rover = Rover::new(Parser::new());
+
+        let trace = rover.try_trace(String::from("5 5\n1 2 N\nMRMM")).unwrap();
+
+        assert_eq!(
+            vec![(1, 3), (1, 3), (2, 3), (3, 3)],
+            trace
+                .iter()
+                .map(|step| (
+                    step.position().coordinate().x(),
+                    step.position().coordinate().y()
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["1 3 N", "1 3 E", "2 3 E", "3 3 E"],
+            trace
+                .iter()
+                .map(|step| step.position().to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn ends_where_execute_reports_the_rover() {
+        let mut traced = Rover::new(Parser::new());
+        let mut executed = Rover::new(Parser::new());
+        let instructions = "5 5\n3 3 E\nMMRMMRMRRM";
+
+        let trace = traced.try_trace(String::from(instructions)).unwrap();
+
+        assert_eq!(
+            format!(
+                "M {}",
+                executed.execute(String::from(instructions)).to_string()
+            ),
+            trace.iter().last().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn stops_recording_when_the_rover_halts() {
+        let mut rover = Rover::new(Parser::new()).with_obstacles("#.\n..".parse().unwrap());
+
+        let trace = rover.try_trace(String::from("1 1\n0 0 N\nMRM")).unwrap();
+
+        assert_eq!("0 0 N\nM 0 0 N", trace.to_string());
+    }
+
+    #[test]
+    fn has_no_steps_without_commands() {
+        let mut rover = Rover::new(Parser::new());
+
+        let trace = rover.try_trace(String::from("5 5\n1 2 N")).unwrap();
+
+        assert!(trace.is_empty());
+        assert_eq!("1 2 N", trace.to_string());
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_energy_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test]
+    fn rover_without_a_battery_never_runs_out() {
+        let mut rover = Rover::new(Parser::new());
+
+        assert_eq!(
+            "1 3 N",
+            rover
+                .execute(String::from("5 5\n1 2 N\nLMLMLMLMM"))
+                .to_string()
+        );
+        assert_eq!(None, rover.charge());
+    }
+
+    #[test_case(20, "1 3 N", 6 ; "with charge to spare")]
+    #[test_case(14, "1 3 N", 0 ; "with exactly enough charge")]
+    #[test_case(5, "0 2 S DEPLETED", 1 ; "running out")]
+    #[test_case(0, "1 2 N DEPLETED", 0 ; "with an empty battery")]
+    fn moves_cost_more_than_turns(capacity: u32, expected: &str, charge: u32) {
+        let mut rover = Rover::new(Parser::new()).with_battery(capacity);
+
+        assert_eq!(
+            expected,
+            rover
+                .execute(String::from("5 5\n1 2 N\nLMLMLMLMM"))
+                .to_string()
+        );
+        assert_eq!(Some(charge), rover.charge());
+    }
+
+    #[test_case("U", 8 ; "u turn costs two turns")]
+    #[test_case("B", 5 ; "backward costs a move")]
+    #[test_case("H", 7 ; "hold")]
+    fn every_command_has_a_cost(commands: &str, charge: u32) {
+        let costs = EnergyCosts::new()
+            .with_movement(5)
+            .with_turn(1)
+            .with_hold(3);
+        let mut rover = Rover::new(Parser::new())
+            .with_battery(10)
+            .with_energy_costs(costs);
+
+        rover.execute(format!("5 5\n1 2 N\n{}", commands));
+
+        assert_eq!(Some(charge), rover.charge());
+    }
+
+    #[test]
+    fn u_turn_cost_saturates_at_the_largest_turn_cost() {
+        let mut rover = Rover::new(Parser::new())
+            .with_battery(u32::MAX)
+            .with_energy_costs(EnergyCosts::new().with_turn(u32::MAX));
+
+        assert_eq!(
+            "1 2 S",
+            rover.execute(String::from("5 5\n1 2 N\nU")).to_string()
+        );
+        assert_eq!(Some(0), rover.charge());
+    }
+
+    #[test]
+    fn battery_is_recharged_for_every_run() {
+        let mut rover = Rover::new(Parser::new()).with_battery(4);
+
+        rover.execute(String::from("5 5\n1 2 N\nMMMM"));
+        rover.execute(String::from("5 5\n1 2 N\nM"));
+
+        assert_eq!(Some(2), rover.charge());
+    }
+
+    #[test]
+    fn depleted_rover_trace_stops_at_the_last_command_it_could_afford() {
+        let mut rover = Rover::new(Parser::new()).with_battery(3);
+
+        let trace = rover.try_trace(String::from("5 5\n1 2 N\nMRM")).unwrap();
 
-    fn to_string(&self) -> String {
-        String::from("N")
+        assert_eq!(2, trace.len());
     }
-}
 
-impl Direction for South {
-    fn turn_left(&self) -> Box<dyn Direction> {
-        Box::new(East)
-    }
-    fn turn_right(&self) -> Box<dyn Direction> {
-        Box::new(West)
-    }
+    #[test]
+    fn mission_costs_are_configurable() {
+        let mut mission = Mission::new(Parser::new())
+            .with_battery(6)
+            .with_energy_costs(EnergyCosts::new().with_movement(3).with_turn(2));
 
-    fn move_vector(&self) -> DirectionMoveVector {
-        DirectionMoveVector(0, -1)
+        assert_eq!(
+            vec!["0 2 W DEPLETED", "5 3 E"],
+            mission.execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMM"))
+        );
+        assert_eq!(&[Some(1), Some(0)], mission.charges());
     }
 
-    fn to_string(&self) -> String {
-        String::from("S")
+    #[test]
+    fn mission_without_batteries_reports_no_charge() {
+        let mut mission = Mission::new(Parser::new());
+
+        mission.execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMM"));
+
+        assert_eq!(&[None, None], mission.charges());
     }
 }
 
-impl Direction for East {
-    fn turn_left(&self) -> Box<dyn Direction> {
-        Box::new(North)
-    }
+#[cfg(test)]
+mod mars_rover_2_terrain_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
 
-    fn turn_right(&self) -> Box<dyn Direction> {
-        Box::new(South)
+    fn terrain() -> TerrainMap {
+        "\
+...
+.c.
+.r."
+        .parse()
+        .unwrap()
     }
 
-    fn move_vector(&self) -> DirectionMoveVector {
-        DirectionMoveVector(1, 0)
-    }
+    #[test]
+    fn rejects_unknown_terrain_cells() {
+        let terrain = "..\n.x".parse::<TerrainMap>();
 
-    fn to_string(&self) -> String {
-        String::from("E")
+        assert_eq!(
+            Err(RoverParseError::InvalidMapCell {
+                line: 2,
+                column: 2,
+                token: String::from("x")
+            }),
+            terrain.map(|_| ())
+        );
     }
-}
 
-impl Direction for West {
-    fn turn_left(&self) -> Box<dyn Direction> {
-        Box::new(South)
-    }
-    fn turn_right(&self) -> Box<dyn Direction> {
-        Box::new(North)
+    #[test]
+    fn craters_are_impassable_by_default() {
+        let mut rover = Rover::new(Parser::new()).with_terrain(terrain());
+
+        assert_eq!(
+            "O:1 0 N",
+            rover.execute(String::from("2 2\n1 0 N\nMM")).to_string()
+        );
     }
 
-    fn move_vector(&self) -> DirectionMoveVector {
-        DirectionMoveVector(-1, 0)
+    #[test]
+    fn impassable_terrain_is_configurable() {
+        let mut rover = Rover::new(Parser::new()).with_terrain(
+            terrain()
+                .with_cost(Terrain::Crater, 4)
+                .with_impassable(Terrain::Rock),
+        );
+
+        assert_eq!(
+            "1 2 N",
+            rover.execute(String::from("2 2\n1 1 N\nM")).to_string()
+        );
+        assert_eq!(
+            "O:1 1 S",
+            rover.execute(String::from("2 2\n1 1 S\nM")).to_string()
+        );
     }
 
-    fn to_string(&self) -> String {
-        String::from("W")
+    #[test_case("2 2\n0 0 E\nM", 6 ; "into rock")]
+    #[test_case("2 2\n1 0 W\nM", 2 ; "onto sand")]
+    #[test_case("2 2\n1 2 N\nB", 8 ; "backward into a crater")]
+    #[test_case("2 2\n0 0 E\nL", 1 ; "turns are not multiplied")]
+    fn terrain_multiplies_the_energy_of_a_move(instructions: &str, spent: u32) {
+        let mut rover = Rover::new(Parser::new())
+            .with_terrain(terrain().with_cost(Terrain::Crater, 4))
+            .with_battery(10);
+
+        rover.execute(String::from(instructions));
+
+        assert_eq!(Some(10 - spent), rover.charge());
     }
-}
 
-struct DirectionFactory;
+    #[test]
+    fn move_cost_saturates_on_costly_terrain() {
+        let mut rover = Rover::new(Parser::new())
+            .with_terrain(terrain())
+            .with_energy_costs(EnergyCosts::new().with_movement(u32::MAX / 2))
+            .with_battery(u32::MAX);
 
-impl DirectionFactory {
-    fn create(input: &str) -> Box<dyn Direction> {
-        match input {
-            "N" => Box::new(North),
-            "W" => Box::new(West),
-            "S" => Box::new(South),
-            "E" => Box::new(East),
-            _ => Box::new(North),
-        }
+        assert_eq!(
+            "1 0 E",
+            rover.execute(String::from("2 2\n0 0 E\nM")).to_string()
+        );
+        assert_eq!(Some(0), rover.charge());
     }
-}
 
-trait RoverCommand {
-    fn execute(&self, rover: &mut Rover);
-}
+    #[test]
+    fn accumulated_cost_saturates_without_a_battery() {
+        let mut rover = Rover::new(Parser::new())
+            .with_energy_costs(EnergyCosts::new().with_movement(u32::MAX / 2 + 1));
 
-struct MoveForward;
+        let trace = rover.try_trace(String::from("5 5\n1 1 N\nMM")).unwrap();
 
-struct TurnLeft;
+        assert_eq!(u32::MAX, trace.cost());
+    }
 
-struct TurnRight;
+    #[test]
+    fn trace_reports_the_accumulated_cost_of_every_step() {
+        let mut rover = Rover::new(Parser::new()).with_terrain(terrain());
 
-struct DoNothing;
+        let trace = rover.try_trace(String::from("2 2\n0 0 E\nMMLM")).unwrap();
 
-impl RoverCommand for MoveForward {
-    fn execute(&self, rover: &mut Rover) {
-        rover.move_forward();
+        assert_eq!(
+            vec![6, 8, 9, 11],
+            trace.iter().map(Step::cost).collect::<Vec<_>>()
+        );
+        assert_eq!(11, trace.cost());
     }
-}
 
-impl RoverCommand for TurnLeft {
-    fn execute(&self, rover: &mut Rover) {
-        rover.turn_left();
+    #[test]
+    fn planner_prefers_cheaper_terrain() {
+        let planner = RoutePlanner::new(Parser::new()).with_terrain(
+            ".r.\n.r.\n..."
+                .parse::<TerrainMap>()
+                .unwrap()
+                .with_cost(Terrain::Rock, 10),
+        );
+
+        assert_eq!(
+            Ok(Some(String::from("MMLMMLMM"))),
+            planner.plan(String::from("2 2\n0 2 S\n2 2"))
+        );
     }
-}
 
-impl RoverCommand for TurnRight {
-    fn execute(&self, rover: &mut Rover) {
-        rover.turn_right();
+    #[test]
+    fn planner_crosses_rock_when_it_is_cheaper_than_going_around() {
+        let planner =
+            RoutePlanner::new(Parser::new()).with_terrain(".r.\n.r.\n...".parse().unwrap());
+
+        assert_eq!(
+            Ok(Some(String::from("LMM"))),
+            planner.plan(String::from("2 2\n0 2 S\n2 2"))
+        );
     }
-}
 
-impl RoverCommand for DoNothing {
-    fn execute(&self, _: &mut Rover) {}
-}
+    #[test]
+    fn planner_routes_around_craters() {
+        let planner = RoutePlanner::new(Parser::new()).with_terrain(terrain());
 
-struct Commands {
-    commands: Vec<Box<dyn RoverCommand>>,
+        assert_eq!(Ok(None), planner.plan(String::from("2 2\n1 0 N\n1 1")));
+        assert_eq!(
+            Ok(Some(String::from("LMRMMRM"))),
+            planner.plan(String::from("2 2\n1 0 N\n1 2"))
+        );
+    }
+
+    #[test]
+    fn mission_rovers_share_the_terrain() {
+        let mut mission = Mission::new(Parser::new()).with_terrain(terrain());
+
+        assert_eq!(
+            vec!["O:1 0 N", "O:0 1 E"],
+            mission.execute(String::from("2 2\n1 0 N\nMM\n0 1 E\nMM"))
+        );
+    }
 }
 
-impl Commands {
-    fn new() -> Self {
-        Self { commands: vec![] }
+#[cfg(test)]
+mod mars_rover_2_compass_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    fn eight_way() -> Parser {
+        Parser::new().with_compass(Compass::EightWay)
     }
 
-    fn add(&mut self, cmd: Box<dyn RoverCommand>) {
-        self.commands.push(cmd);
+    #[test_case("N")]
+    #[test_case("NE")]
+    #[test_case("E")]
+    #[test_case("SE")]
+    #[test_case("S")]
+    #[test_case("SW")]
+    #[test_case("W")]
+    #[test_case("NW")]
+    fn factory_accepts_every_point_of_the_compass_rose(heading: &str) {
+        let direction = DirectionFactory::create(Compass::EightWay, heading).unwrap();
+
+        assert_eq!(heading, direction.to_string());
     }
 
-    fn execute(&self, rover: &mut Rover) {
-        self.commands.iter().for_each(|c| c.execute(rover));
+    #[test]
+    fn four_way_factory_has_no_diagonals() {
+        assert!(DirectionFactory::create(Compass::FourWay, "NE").is_none());
     }
 
-    fn create_command(input: &char) -> Box<dyn RoverCommand> {
-        match input {
-            'M' => Box::new(MoveForward),
-            'L' => Box::new(TurnLeft),
-            'R' => Box::new(TurnRight),
-            _ => Box::new(DoNothing),
-        }
+    #[test]
+    fn four_way_parser_rejects_diagonal_headings() {
+        let mut rover = Rover::new(Parser::new());
+
+        assert_eq!(
+            Err(RoverParseError::InvalidDirection {
+                line: 2,
+                column: 5,
+                token: String::from("NE")
+            }),
+            rover.try_execute(String::from("5 5\n1 2 NE\nM"))
+        );
     }
-}
 
-struct Coordinate {
-    x: u8,
-    y: u8,
-}
+    #[test_case("L", "1 2 NW" ; "left")]
+    #[test_case("R", "1 2 NE" ; "right")]
+    #[test_case("LLLLLLLL", "1 2 N" ; "full turn")]
+    #[test_case("U", "1 2 S" ; "u turn")]
+    #[test_case("RRRU", "1 2 NW" ; "u turn from south east")]
+    fn turns_by_forty_five_degrees(commands: &str, expected: &str) {
+        let mut rover = Rover::new(eight_way());
 
-impl fmt::Display for Coordinate {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", self.x, self.y)
+        assert_eq!(
+            expected,
+            rover
+                .execute(format!("5 5\n1 2 N\n{}", commands))
+                .to_string()
+        );
     }
-}
 
-impl Coordinate {
-    fn new(x: u8, y: u8) -> Self {
-        Self { x, y }
+    #[test_case("NE", "2 3 NE")]
+    #[test_case("SE", "2 1 SE")]
+    #[test_case("SW", "0 1 SW")]
+    #[test_case("NW", "0 3 NW")]
+    fn moves_diagonally(heading: &str, expected: &str) {
+        let mut rover = Rover::new(eight_way());
+
+        assert_eq!(
+            expected,
+            rover
+                .execute(format!("5 5\n1 2 {}\nM", heading))
+                .to_string()
+        );
     }
 
-    fn apply_vector(&mut self, vector: DirectionMoveVector) {
-        self.x = (self.x as i8 + vector.0) as u8;
-        self.y = (self.y as i8 + vector.1) as u8;
+    #[test]
+    fn moves_backward_diagonally() {
+        let mut rover = Rover::new(eight_way());
+
+        assert_eq!(
+            "0 1 NE",
+            rover.execute(String::from("5 5\n1 2 NE\nB")).to_string()
+        );
     }
-}
 
-struct Position {
-    coordinate: Coordinate,
-    direction: Box<dyn Direction>,
-}
+    #[test]
+    fn diagonal_moves_respect_the_edge_policy() {
+        let mut rejecting = Rover::new(eight_way());
+        let mut wrapping = Rover::new(eight_way()).with_edge_policy(EdgePolicy::Wrap);
 
-impl Position {
-    fn new(coordinate: Coordinate, direction: Box<dyn Direction>) -> Self {
-        Self {
-            coordinate,
-            direction,
-        }
+        assert_eq!(
+            "5 3 NE",
+            rejecting
+                .execute(String::from("5 5\n5 3 NE\nM"))
+                .to_string()
+        );
+        assert_eq!(
+            "0 4 NE",
+            wrapping.execute(String::from("5 5\n5 3 NE\nM")).to_string()
+        );
     }
 
-    fn turn_left(&mut self) {
-        self.direction = self.direction.turn_left();
+    #[test]
+    fn four_way_rover_is_unchanged() {
+        let mut rover = Rover::new(Parser::new().with_compass(Compass::FourWay));
+
+        assert_eq!(
+            "1 3 N",
+            rover
+                .execute(String::from("5 5\n1 2 N\nLMLMLMLMM"))
+                .to_string()
+        );
     }
 
-    fn turn_right(&mut self) {
-        self.direction = self.direction.turn_right();
+    #[test]
+    fn mission_is_parsed_with_the_compass_rose() {
+        let mut mission = Mission::new(eight_way());
+
+        assert_eq!(
+            vec!["3 4 NE", "1 3 NW"],
+            mission.execute(String::from("5 5\n1 2 NE\nMM\n3 3 SW\nMRRM"))
+        );
     }
 
-    fn move_forward(&mut self) {
-        self.coordinate.apply_vector(self.direction.move_vector())
+    #[test_case("LLLL", "U")]
+    #[test_case("LLL", "LLL")]
+    #[test_case("LLLLL", "RRR")]
+    #[test_case("LLLLLLL", "R")]
+    #[test_case("UL", "RRR")]
+    fn optimizer_collapses_forty_five_degree_turns(commands: &str, expected: &str) {
+        let rover = Rover::new(eight_way());
+
+        assert_eq!(
+            Ok(String::from(expected)),
+            rover.try_optimize(format!("5 5\n2 2 N\n{}", commands))
+        );
     }
-}
 
-impl fmt::Display for Position {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", self.coordinate, self.direction.to_string())
+    #[test]
+    fn planner_cuts_corners() {
+        let planner = RoutePlanner::new(eight_way());
+
+        assert_eq!(
+            Ok(Some(String::from("MMM"))),
+            planner.plan(String::from("5 5\n0 0 NE\n3 3"))
+        );
+        assert_eq!(
+            Ok(Some(String::from("RMMM"))),
+            planner.plan(String::from("5 5\n0 0 N\n3 3 NE"))
+        );
     }
-}
 
-pub struct Parser {}
+    #[test]
+    fn renders_diagonal_headings() {
+        let mut rover = Rover::new(eight_way());
 
-impl Default for Parser {
-    fn default() -> Self {
-        Self::new()
+        rover.execute(String::from("1 1\n0 0 NE\nM"));
+
+        assert_eq!(".↗\n*.", rover.render());
     }
 }
 
-impl Parser {
-    pub fn new() -> Self {
-        Parser {}
+#[cfg(test)]
+mod mars_rover_2_hex_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    fn hex() -> Parser {
+        Parser::new().with_compass(Compass::Hex)
     }
 
-    fn parse(&self, instructions: String) -> (Commands, Position) {
-        let lines: Vec<&str> = instructions.lines().collect();
-        let position = self.parse_position(lines[1]);
+    #[test_case("E")]
+    #[test_case("NE")]
+    #[test_case("NW")]
+    #[test_case("W")]
+    #[test_case("SW")]
+    #[test_case("SE")]
+    fn factory_accepts_the_six_hex_headings(heading: &str) {
+        let direction = DirectionFactory::create(Compass::Hex, heading).unwrap();
 
-        if lines.len() < 3 {
-            return (Commands::new(), position);
-        }
+        assert_eq!(heading, direction.to_string());
+    }
 
-        let commands = self.parse_commands(lines[2]);
+    #[test_case("N")]
+    #[test_case("S")]
+    fn hex_parser_rejects_headings_along_the_vertical(heading: &str) {
+        let mut rover = Rover::new(hex());
 
-        (commands, position)
+        assert_eq!(
+            Err(RoverParseError::InvalidDirection {
+                line: 2,
+                column: 5,
+                token: String::from(heading)
+            }),
+            rover.try_execute(format!("5 5\n2 2 {}\nM", heading))
+        );
     }
 
-    fn parse_position(&self, position: &str) -> Position {
-        let position_parts: Vec<&str> = position.split_whitespace().collect();
-        let x: u8 = position_parts[0].parse().unwrap_or(0);
-        let y: u8 = position_parts[1].parse().unwrap_or(0);
-        let direction = position_parts[2];
+    #[test_case("L", "2 2 NE" ; "left")]
+    #[test_case("R", "2 2 SE" ; "right")]
+    #[test_case("LLLLLL", "2 2 E" ; "full turn")]
+    #[test_case("U", "2 2 W" ; "u turn")]
+    fn turns_by_sixty_degrees(commands: &str, expected: &str) {
+        let mut rover = Rover::new(hex());
 
-        Position::new(Coordinate::new(x, y), DirectionFactory::create(direction))
+        assert_eq!(
+            expected,
+            rover
+                .execute(format!("5 5\n2 2 E\n{}", commands))
+                .to_string()
+        );
     }
 
-    fn parse_commands(&self, commands: &str) -> Commands {
-        let raw_commands: Vec<char> = commands.chars().collect();
-        let mut commands = Commands::new();
+    #[test_case("E", "3 2 E")]
+    #[test_case("NE", "2 3 NE")]
+    #[test_case("NW", "1 3 NW")]
+    #[test_case("W", "1 2 W")]
+    #[test_case("SW", "2 1 SW")]
+    #[test_case("SE", "3 1 SE")]
+    fn moves_to_the_six_neighbours_in_axial_coordinates(heading: &str, expected: &str) {
+        let mut rover = Rover::new(hex());
 
-        raw_commands
-            .iter()
-            .for_each(|c| commands.add(Commands::create_command(c)));
+        assert_eq!(
+            expected,
+            rover
+                .execute(format!("5 5\n2 2 {}\nM", heading))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn same_command_script_drives_a_hex_rover() {
+        let mut rover = Rover::new(hex());
 
-        commands
+        assert_eq!(
+            "0 2 SW",
+            rover
+                .execute(String::from("5 5\n1 2 E\nLMLMLMLMM"))
+                .to_string()
+        );
     }
-}
 
-pub struct Rover {
-    parser: Parser,
-    position: Position,
-}
+    #[test]
+    fn six_moves_around_a_hex_return_to_the_start() {
+        let mut rover = Rover::new(hex());
 
-impl Rover {
-    pub fn new(parser: Parser) -> Self {
-        Rover {
-            position: Position::new(Coordinate::new(0, 0), Box::new(North)),
-            parser,
-        }
+        assert_eq!(
+            "2 2 E",
+            rover.execute(String::from("5 5\n2 2 E\n6(ML)")).to_string()
+        );
     }
 
-    pub fn execute(&mut self, instructions: String) -> String {
-        let (commands, starting_position) = self.parser.parse(instructions);
-        self.update_position(starting_position);
+    #[test]
+    fn renders_rows_offset_by_half_a_cell() {
+        let mut rover = Rover::new(hex());
 
-        commands.execute(self);
+        rover.execute(String::from("2 1\n0 0 E\nM"));
 
-        format!("{}", self.position)
+        assert_eq!(" . . .\n* > .", rover.render());
     }
 
-    fn update_position(&mut self, position: Position) {
-        self.position = position;
-    }
+    #[test]
+    fn renders_obstacles_on_the_hex_grid() {
+        let mut rover = Rover::new(hex()).with_obstacles("..#\n...\n...".parse().unwrap());
 
-    fn turn_left(&mut self) {
-        self.position.turn_left();
+        rover.execute(String::from("2 2\n0 0 NE\nMM"));
+
+        assert_eq!("  ↗ . #\n * . .\n* . .", rover.render());
     }
 
-    fn turn_right(&mut self) {
-        self.position.turn_right();
+    #[test]
+    fn planner_uses_hex_distance() {
+        let planner = RoutePlanner::new(hex());
+
+        assert_eq!(
+            Ok(Some(String::from("MM"))),
+            planner.plan(String::from("5 5\n3 0 NW\n1 2"))
+        );
+        assert_eq!(
+            Ok(Some(String::from("MMLMM"))),
+            planner.plan(String::from("5 5\n0 0 E\n2 2"))
+        );
     }
 
-    fn move_forward(&mut self) {
-        self.position.move_forward();
+    #[test]
+    fn optimizer_knows_a_u_turn_is_three_turns() {
+        let rover = Rover::new(hex());
+
+        assert_eq!(
+            Ok(String::from("U")),
+            rover.try_optimize(String::from("5 5\n2 2 E\nLLL"))
+        );
     }
 }
 
 #[cfg(test)]
-mod mars_rover_2_unit_tests {
+mod mars_rover_2_drone_tests {
     use crate::mars_rover2::*;
     use pretty_assertions::assert_eq;
+    use test_case::test_case;
 
-    #[test]
-    fn stays_in_same_position_when_no_commands_are_sent() {
+    #[test_case("", "1 2 0 N" ; "on the ground")]
+    #[test_case("CMM", "1 2 2 N UP" ; "climb")]
+    #[test_case("CMMDM", "1 3 2 N" ; "level out")]
+    #[test_case("CR", "1 2 0 E UP" ; "yaw while climbing")]
+    #[test_case("CD", "1 2 0 N" ; "climb then descend")]
+    #[test_case("CC", "1 2 0 N UP" ; "climbing twice keeps the pitch")]
+    #[test_case("DM", "1 2 0 N DOWN" ; "cannot descend below the ground")]
+    #[test_case("CMMMMM", "1 2 3 N UP" ; "stops at the ceiling")]
+    fn flies_in_three_dimensions(commands: &str, expected: &str) {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\n"));
-
-        assert_eq!(String::from("1 1 N"), position);
+        assert_eq!(
+            expected,
+            rover
+                .execute(format!("5 5 3\n1 2 0 N\n{}", commands))
+                .to_string()
+        );
     }
 
-    #[test]
-    fn turn_left_turns_from_north_to_west() {
+    #[test_case("CM", 1, "C" ; "climb")]
+    #[test_case("MDM", 2, "D" ; "descend")]
+    fn pitch_commands_need_a_three_dimensional_plateau(commands: &str, column: usize, token: &str) {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nL"));
-
-        assert_eq!(String::from("1 1 W"), position);
+        assert_eq!(
+            Err(RoverParseError::InvalidCommand {
+                line: 3,
+                column,
+                token: String::from(token)
+            }),
+            rover.try_execute(format!("5 5\n1 1 N\n{}", commands))
+        );
     }
 
     #[test]
-    fn turn_left_turns_from_west_to_south() {
+    fn parses_a_pitched_heading() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 W\nL"));
-
-        assert_eq!(String::from("1 1 S"), position);
+        assert_eq!(
+            "1 2 2 W DOWN",
+            rover
+                .execute(String::from("5 5 3\n1 2 3 N DOWN\nLM"))
+                .to_string()
+        );
     }
 
     #[test]
-    fn turn_left_turns_from_south_to_east() {
+    fn rejects_an_unknown_pitch() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 S\nL"));
-
-        assert_eq!(String::from("1 1 E"), position);
+        assert_eq!(
+            Err(RoverParseError::InvalidDirection {
+                line: 2,
+                column: 9,
+                token: String::from("SIDEWAYS")
+            }),
+            rover.try_execute(String::from("5 5 3\n1 2 0 N SIDEWAYS\nM"))
+        );
     }
 
     #[test]
-    fn turn_left_turns_from_east_to_north() {
+    fn requires_an_altitude_on_a_three_dimensional_plateau() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 E\nL"));
-
-        assert_eq!(String::from("1 1 N"), position);
+        assert_eq!(
+            Err(RoverParseError::InvalidCoordinate {
+                line: 2,
+                column: 5,
+                token: String::from("N")
+            }),
+            rover.try_execute(String::from("5 5 3\n1 2 N\nM"))
+        );
     }
 
     #[test]
-    fn turn_left_four_time_ends_in_same_position() {
+    fn rejects_a_start_above_the_ceiling() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nLLLL"));
-
-        assert_eq!(String::from("1 1 N"), position);
+        assert_eq!(
+            Err(RoverParseError::OutsidePlateau {
+                line: 2,
+                column: 1,
+                token: String::from("1 2 4")
+            }),
+            rover.try_execute(String::from("5 5 3\n1 2 4 N\nM"))
+        );
     }
 
     #[test]
-    fn turn_right_turns_from_north_to_east() {
-        let mut rover = Rover::new(Parser::new());
+    fn leaving_through_the_ground_loses_the_drone() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nR"));
-
-        assert_eq!(String::from("1 1 E"), position);
+        assert_eq!(
+            "1 2 0 N DOWN LOST",
+            rover
+                .execute(String::from("5 5 3\n1 2 1 N\nDMM"))
+                .to_string()
+        );
     }
 
     #[test]
-    fn turn_right_turns_from_east_to_south() {
-        let mut rover = Rover::new(Parser::new());
+    fn flies_over_obstacles() {
+        let obstacles: ObstacleMap = "......\n......\n.#....\n......\n......\n......"
+            .parse()
+            .unwrap();
+        let mut drone = Rover::new(Parser::new()).with_obstacles(obstacles.clone());
+        let mut rover = Rover::new(Parser::new()).with_obstacles(obstacles);
 
-        let position = rover.execute(String::from("5 5\n1 1 E\nR"));
-
-        assert_eq!(String::from("1 1 S"), position);
+        assert_eq!(
+            "1 4 1 N",
+            drone
+                .execute(String::from("5 5 3\n1 2 1 N\nMM"))
+                .to_string()
+        );
+        assert_eq!(
+            "O:1 2 0 N",
+            rover
+                .execute(String::from("5 5 3\n1 2 0 N\nMM"))
+                .to_string()
+        );
     }
 
     #[test]
-    fn turn_right_turns_from_south_to_west() {
+    fn planner_climbs_to_the_goal_altitude() {
+        let planner = RoutePlanner::new(Parser::new());
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 S\nR"));
+        let commands = planner
+            .plan(String::from("5 5 3\n0 0 0 N\n0 2 2"))
+            .unwrap()
+            .unwrap();
 
-        assert_eq!(String::from("1 1 W"), position);
+        assert_eq!("MMCMM", commands);
+        assert_eq!(
+            "0 2 2 N UP",
+            rover
+                .execute(format!("5 5 3\n0 0 0 N\n{}", commands))
+                .to_string()
+        );
     }
 
     #[test]
-    fn turn_right_turns_from_west_to_north() {
+    fn retraces_a_flight() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 W\nR"));
+        rover.execute(String::from("5 5 3\n1 1 0 N\nCMMDM"));
 
-        assert_eq!(String::from("1 1 N"), position);
+        assert_eq!(
+            Some(String::from("BCBBD")),
+            rover.return_to_base(ReturnStrategy::Retrace)
+        );
     }
+}
+
+#[cfg(test)]
+mod mars_rover_2_report_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
 
     #[test]
-    fn turn_right_four_time_ends_in_same_position() {
+    fn reports_the_final_position() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nRRRR"));
+        let report = rover.execute(String::from("5 5\n1 2 N\nLMLMLMLMM"));
+        let position = report.position();
 
-        assert_eq!(String::from("1 1 N"), position);
+        assert_eq!(
+            (1, 3, None),
+            (
+                position.coordinate().x(),
+                position.coordinate().y(),
+                position.coordinate().z()
+            )
+        );
+        assert_eq!("N", position.direction().to_string());
+        assert_eq!(&StopReason::Completed, report.stop_reason());
     }
 
-    #[test]
-    fn move_north() {
-        let mut rover = Rover::new(Parser::new());
+    #[test_case(EdgePolicy::Reject, 2, 1, StopReason::Completed ; "reject")]
+    #[test_case(EdgePolicy::Stop, 0, 3, StopReason::Stopped ; "stop")]
+    #[test_case(EdgePolicy::Lost, 0, 3, StopReason::Lost ; "lost")]
+    fn counts_executed_and_skipped_commands(
+        edge_policy: EdgePolicy,
+        executed: usize,
+        skipped: usize,
+        stop_reason: StopReason,
+    ) {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(edge_policy);
 
-        let position = rover.execute(String::from("5 5\n1 1 N\nM"));
+        let report = rover.execute(String::from("5 5\n1 5 N\nMRM"));
 
-        assert_eq!(String::from("1 2 N"), position);
+        assert_eq!(
+            (executed, skipped, &stop_reason),
+            (report.executed(), report.skipped(), report.stop_reason())
+        );
     }
 
     #[test]
-    fn move_south() {
-        let mut rover = Rover::new(Parser::new());
+    fn reports_a_blocked_rover() {
+        let mut rover = Rover::new(Parser::new()).with_obstacles("#.\n..".parse().unwrap());
 
-        let position = rover.execute(String::from("5 5\n1 1 S\nM"));
+        let report = rover.execute(String::from("1 1\n0 0 N\nMRM"));
 
-        assert_eq!(String::from("1 0 S"), position);
+        assert_eq!(&StopReason::Blocked, report.stop_reason());
+        assert_eq!((0, 3), (report.executed(), report.skipped()));
+        assert_eq!("O:0 0 N", report.to_string());
     }
 
     #[test]
-    fn move_west() {
-        let mut rover = Rover::new(Parser::new());
+    fn rejected_moves_count_as_skipped() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Reject);
 
-        let position = rover.execute(String::from("5 5\n1 1 W\nM"));
+        let report = rover.execute(String::from("5 5\n1 5 N\nMMMM"));
 
-        assert_eq!(String::from("0 1 W"), position);
+        assert_eq!(&StopReason::Completed, report.stop_reason());
+        assert_eq!((0, 4), (report.executed(), report.skipped()));
     }
 
     #[test]
-    fn move_east() {
-        let mut rover = Rover::new(Parser::new());
+    fn reports_a_depleted_battery() {
+        let mut rover = Rover::new(Parser::new()).with_battery(3);
 
-        let position = rover.execute(String::from("5 5\n1 1 E\nM"));
+        let report = rover.execute(String::from("5 5\n1 1 N\nMMM"));
 
-        assert_eq!(String::from("2 1 E"), position);
+        assert_eq!(&StopReason::Depleted, report.stop_reason());
+        assert_eq!((1, 2), (report.executed(), report.skipped()));
+        assert_eq!("1 2 N DEPLETED", report.to_string());
     }
-}
-
-#[cfg(test)]
-mod mars_rover_2_acceptance_tests {
-    use crate::mars_rover2::*;
-    use pretty_assertions::assert_eq;
 
     #[test]
-    fn turn_left_and_move() {
+    fn reports_invalid_instructions_as_the_stop_reason() {
         let mut rover = Rover::new(Parser::new());
+        let error = RoverParseError::InvalidDirection {
+            line: 2,
+            column: 5,
+            token: String::from("X"),
+        };
 
-        let position = rover.execute(String::from("5 5\n1 2 N\nLMLMLMLMM"));
+        let report = rover.execute(String::from("5 5\n1 1 X\nM"));
 
-        assert_eq!(String::from("1 3 N"), position);
+        assert_eq!(&StopReason::Invalid(error.clone()), report.stop_reason());
+        assert_eq!((0, 0), (report.executed(), report.skipped()));
+        assert_eq!(error.to_string(), report.to_string());
     }
 
     #[test]
-    fn turn_right_and_move() {
+    fn try_execute_returns_the_report() {
         let mut rover = Rover::new(Parser::new());
 
-        let position = rover.execute(String::from("5 5\n3 3 E\nMMRMMRMRRM"));
+        let report = rover
+            .try_execute(String::from("5 5\n3 3 E\nMMRMMRMRRM"))
+            .unwrap();
 
-        assert_eq!(String::from("5 1 E"), position);
+        assert_eq!("5 1 E", report.to_string());
+        assert_eq!((10, 0), (report.executed(), report.skipped()));
     }
 }
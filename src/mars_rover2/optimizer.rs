@@ -0,0 +1,252 @@
+use std::cmp::Ordering;
+
+use super::{Commands, Position, Rover, RoverParseError, Status};
+
+impl Rover {
+    pub fn try_optimize(&self, instructions: String) -> Result<String, RoverParseError> {
+        let mut scratch = self.scratch();
+        let commands = scratch.deploy(instructions)?;
+
+        Ok(commands.optimize(&mut scratch).symbols())
+    }
+
+    fn scratch(&self) -> Rover {
+        let mut scratch = Rover::new(self.parser.clone())
+            .with_edge_policy(self.edge_policy)
+            .with_obstacles(self.obstacles.clone())
+            .with_terrain(self.terrain.clone())
+            .with_energy_costs(self.energy_costs);
+        scratch.capacity = self.capacity;
+        scratch.charge = self.capacity;
+
+        scratch
+    }
+}
+
+impl Commands {
+    fn optimize(&self, rover: &mut Rover) -> Commands {
+        let points = rover.position.direction.compass().points();
+        let mut positions: Vec<Position> = vec![rover.position.clone()];
+        let mut kept = Vec::new();
+
+        for command in &self.commands {
+            if !rover.is_active() {
+                break;
+            }
+
+            rover.consume(command.energy(rover));
+            if rover.status == Status::Depleted {
+                return Self::from_symbols(&self.symbols());
+            }
+            command.execute(rover);
+
+            match positions
+                .iter()
+                .position(|position| rover.is_active() && *position == rover.position)
+            {
+                Some(index) => {
+                    positions.truncate(index + 1);
+                    kept.truncate(index);
+                }
+                None => {
+                    positions.push(rover.position.clone());
+                    kept.push(command.symbol());
+                }
+            }
+        }
+
+        Self::collapse_rotations(&kept, points)
+    }
+
+    fn collapse_rotations(symbols: &[char], points: usize) -> Commands {
+        let mut collapsed = String::new();
+        let mut turns = 0;
+
+        for symbol in symbols {
+            match symbol {
+                'L' => turns += 1,
+                'R' => turns += points - 1,
+                'U' => turns += points / 2,
+                _ => {
+                    collapsed.push_str(&Self::rotation(turns, points));
+                    collapsed.push(*symbol);
+                    turns = 0;
+                }
+            }
+        }
+        collapsed.push_str(&Self::rotation(turns, points));
+
+        Self::from_symbols(&collapsed)
+    }
+
+    fn from_symbols(symbols: &str) -> Commands {
+        let mut commands = Commands::new();
+        symbols
+            .chars()
+            .filter_map(|symbol| Commands::create_command(&symbol))
+            .for_each(|command| commands.add(command));
+
+        commands
+    }
+
+    fn rotation(turns: usize, points: usize) -> String {
+        let turns = turns % points;
+
+        match turns.cmp(&(points / 2)) {
+            Ordering::Less => "L".repeat(turns),
+            Ordering::Equal => String::from("U"),
+            Ordering::Greater => "R".repeat(points - turns),
+        }
+    }
+
+    fn symbols(&self) -> String {
+        self.commands
+            .iter()
+            .map(|command| command.symbol())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_optimizer_tests {
+    use crate::mars_rover2::test_support::rock_map;
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    #[test_case("LLLL", "" ; "full turn")]
+    #[test_case("LLL", "R" ; "three lefts")]
+    #[test_case("RRR", "L" ; "three rights")]
+    #[test_case("LR", "" ; "opposite turns")]
+    #[test_case("LL", "U" ; "half turn")]
+    #[test_case("LUL", "" ; "u turn between lefts")]
+    #[test_case("HMH", "M" ; "holds")]
+    #[test_case("MB", "" ; "forward and back")]
+    #[test_case("MLRMB", "M" ; "net zero after collapse")]
+    #[test_case("MRMRMRMR", "" ; "square")]
+    #[test_case("MRRRMLLLM", "MLMRM" ; "rotations between moves")]
+    fn removes_redundant_commands(commands: &str, expected: &str) {
+        let rover = Rover::new(Parser::new());
+
+        assert_eq!(
+            Ok(String::from(expected)),
+            rover.try_optimize(format!("5 5\n2 2 N\n{}", commands))
+        );
+    }
+
+    #[test]
+    fn keeps_the_return_move_when_the_first_move_is_rejected() {
+        let rover = Rover::new(Parser::new());
+
+        assert_eq!(
+            Ok(String::from("B")),
+            rover.try_optimize(String::from("5 5\n2 5 N\nMB"))
+        );
+    }
+
+    #[test]
+    fn drops_everything_after_the_rover_halts() {
+        let rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Lost);
+
+        assert_eq!(
+            Ok(String::from("M")),
+            rover.try_optimize(String::from("5 5\n2 5 N\nMRRMM"))
+        );
+    }
+
+    #[test]
+    fn keeps_a_program_that_runs_out_of_energy() {
+        let rover = Rover::new(Parser::new()).with_battery(2);
+
+        assert_eq!(
+            Ok(String::from("LRMM")),
+            rover.try_optimize(String::from("5 5\n1 1 N\nLRMM"))
+        );
+    }
+
+    #[test]
+    fn optimizes_a_program_the_battery_can_afford() {
+        let rover = Rover::new(Parser::new()).with_battery(6);
+
+        assert_eq!(
+            Ok(String::from("MM")),
+            rover.try_optimize(String::from("5 5\n1 1 N\nLRMM"))
+        );
+    }
+
+    #[test]
+    fn optimizing_leaves_the_rover_untouched() {
+        let mut rover = Rover::new(Parser::new());
+        rover.execute(String::from("5 5\n0 0 N\nMM"));
+        let render = rover.render();
+        let shortest = rover.return_to_base(ReturnStrategy::Shortest);
+
+        rover.try_optimize(String::from("5 5\n4 4 S\nMM")).unwrap();
+
+        assert_eq!(render, rover.render());
+        assert_eq!(shortest, rover.return_to_base(ReturnStrategy::Shortest));
+        assert_eq!(
+            Some(String::from("BB")),
+            rover.return_to_base(ReturnStrategy::Retrace)
+        );
+    }
+
+    #[test]
+    fn keeps_a_move_into_an_obstacle() {
+        let rover = Rover::new(Parser::new()).with_obstacles("#.\n..".parse().unwrap());
+
+        assert_eq!(
+            Ok(String::from("LM")),
+            rover.try_optimize(String::from("1 1\n1 1 N\nLLRMM"))
+        );
+    }
+
+    prop_compose! {
+        fn program()(width in 0u32..5, height in 0u32..5)(
+            (map, start) in rock_map(width, height, 0.15),
+            heading in proptest::sample::select(vec!["N", "E", "S", "W"]),
+            commands in "[LRMBUH]{0,30}",
+            width in Just(width),
+            height in Just(height),
+        ) -> (String, String, String) {
+            (map, format!("{} {}\n{} {} {}", width, height, start.0, start.1, heading), commands)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn optimized_programs_end_in_the_same_position(
+            (map, deployment, commands) in program(),
+            edge_policy in proptest::sample::select(vec![
+                EdgePolicy::Stop,
+                EdgePolicy::Reject,
+                EdgePolicy::Lost,
+                EdgePolicy::Wrap,
+            ]),
+            battery in proptest::option::of(0u32..40),
+        ) {
+            let obstacles: ObstacleMap = map.parse().unwrap();
+            let rover = || {
+                let rover = Rover::new(Parser::new())
+                    .with_edge_policy(edge_policy)
+                    .with_obstacles(obstacles.clone());
+
+                match battery {
+                    Some(capacity) => rover.with_battery(capacity),
+                    None => rover,
+                }
+            };
+
+            let optimized = rover()
+                .try_optimize(format!("{}\n{}", deployment, commands))
+                .unwrap();
+
+            prop_assert!(optimized.len() <= commands.len());
+            prop_assert_eq!(
+                rover().execute(format!("{}\n{}", deployment, commands)).to_string(),
+                rover().execute(format!("{}\n{}", deployment, optimized)).to_string()
+            );
+        }
+    }
+}
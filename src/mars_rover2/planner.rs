@@ -0,0 +1,773 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use super::{
+    Compass, Coordinate, Direction, DirectionFactory, ObstacleMap, Parser, Plateau, Position,
+    Rover, RoverParseError, TerrainMap, Tokens,
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReturnStrategy {
+    Retrace,
+    Shortest,
+}
+
+type Goal = (Coordinate, Option<Box<dyn Direction>>);
+
+pub struct RoutePlanner {
+    parser: Parser,
+    obstacles: ObstacleMap,
+    terrain: TerrainMap,
+}
+
+impl RoutePlanner {
+    pub fn new(parser: Parser) -> Self {
+        Self {
+            parser,
+            obstacles: ObstacleMap::default(),
+            terrain: TerrainMap::default(),
+        }
+    }
+
+    pub fn with_obstacles(mut self, obstacles: ObstacleMap) -> Self {
+        self.obstacles = obstacles;
+        self
+    }
+
+    pub fn with_terrain(mut self, terrain: TerrainMap) -> Self {
+        self.terrain = terrain;
+        self
+    }
+
+    pub fn plan(&self, instructions: String) -> Result<Option<String>, RoverParseError> {
+        let (plateau, start, (goal, heading)) = self.parser.parse_route(instructions)?;
+
+        let ground = Ground::new(&plateau, &self.obstacles, &self.terrain);
+
+        Ok(route(&ground, &start, &goal, heading.as_deref()))
+    }
+}
+
+pub struct CoveragePlanner {
+    parser: Parser,
+    obstacles: ObstacleMap,
+    terrain: TerrainMap,
+}
+
+impl CoveragePlanner {
+    pub fn new(parser: Parser) -> Self {
+        Self {
+            parser,
+            obstacles: ObstacleMap::default(),
+            terrain: TerrainMap::default(),
+        }
+    }
+
+    pub fn with_obstacles(mut self, obstacles: ObstacleMap) -> Self {
+        self.obstacles = obstacles;
+        self
+    }
+
+    pub fn with_terrain(mut self, terrain: TerrainMap) -> Self {
+        self.terrain = terrain;
+        self
+    }
+
+    pub fn plan(&self, instructions: String) -> Result<Coverage, RoverParseError> {
+        let (plateau, start) = self.parser.parse_survey(instructions)?;
+        let ground = Ground::new(&plateau, &self.obstacles, &self.terrain);
+        let reachable = reachable(&ground, &start);
+        let mut position = start;
+        let mut visited = HashSet::from([position.coordinate]);
+        let mut commands = String::new();
+        let mut unreachable = Vec::new();
+
+        for target in sweep(&plateau, position.coordinate.z).filter(|cell| ground.is_open(cell)) {
+            if visited.contains(&target) {
+                continue;
+            }
+
+            let leg = reachable
+                .contains(&target)
+                .then(|| route(&ground, &position, &target, None))
+                .flatten();
+            let Some(leg) = leg else {
+                unreachable.push(target);
+                continue;
+            };
+
+            for command in leg.chars() {
+                advance(&mut position, command);
+                visited.insert(position.coordinate);
+            }
+            commands.push_str(&leg);
+        }
+
+        Ok(Coverage {
+            commands,
+            unreachable,
+        })
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Coverage {
+    commands: String,
+    unreachable: Vec<Coordinate>,
+}
+
+impl Coverage {
+    pub fn commands(&self) -> &str {
+        &self.commands
+    }
+
+    pub fn unreachable(&self) -> Vec<(u32, u32)> {
+        self.unreachable
+            .iter()
+            .map(|cell| (cell.x, cell.y))
+            .collect()
+    }
+}
+
+impl Rover {
+    pub fn return_to_base(&self, strategy: ReturnStrategy) -> Option<String> {
+        match strategy {
+            ReturnStrategy::Retrace => Some(self.retrace()),
+            ReturnStrategy::Shortest => self.plateau.and_then(|plateau| {
+                route(
+                    &Ground::new(&plateau, &self.obstacles, &self.terrain),
+                    &self.position,
+                    &self.trace.start.coordinate,
+                    Some(self.trace.start.direction.as_ref()),
+                )
+            }),
+        }
+    }
+
+    fn retrace(&self) -> String {
+        let positions: Vec<&Position> = self.trace.positions().collect();
+
+        self.trace
+            .iter()
+            .zip(positions.windows(2))
+            .rev()
+            .filter_map(|(step, positions)| {
+                let moved = positions[0].coordinate != positions[1].coordinate;
+                let turned = *positions[0].direction != *positions[1].direction;
+
+                match step.command() {
+                    'M' if moved => Some('B'),
+                    'B' if moved => Some('M'),
+                    'L' if turned => Some('R'),
+                    'R' if turned => Some('L'),
+                    'U' if turned => Some('U'),
+                    'C' if turned => Some('D'),
+                    'D' if turned => Some('C'),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Parser {
+    fn parse_route(
+        &self,
+        instructions: String,
+    ) -> Result<(Plateau, Position, Goal), RoverParseError> {
+        let lines: Vec<&str> = instructions.trim_end().lines().collect();
+        let (plateau, start) = self.parse_start(&lines)?;
+        let goal = lines.get(2).ok_or(RoverParseError::MissingLine {
+            line: 3,
+            expected: "goal coordinate",
+        })?;
+        let goal = self.parse_goal(&plateau, 3, goal)?;
+        Self::no_more_lines(&lines, 3)?;
+
+        Ok((plateau, start, goal))
+    }
+
+    fn parse_survey(&self, instructions: String) -> Result<(Plateau, Position), RoverParseError> {
+        let lines: Vec<&str> = instructions.trim_end().lines().collect();
+        let (plateau, start) = self.parse_start(&lines)?;
+        Self::no_more_lines(&lines, 2)?;
+
+        Ok((plateau, start))
+    }
+
+    fn parse_start(&self, lines: &[&str]) -> Result<(Plateau, Position), RoverParseError> {
+        let plateau = lines.first().ok_or(RoverParseError::MissingLine {
+            line: 1,
+            expected: "plateau size",
+        })?;
+        let plateau = self.parse_plateau(1, plateau)?;
+        let start = lines.get(1).ok_or(RoverParseError::MissingLine {
+            line: 2,
+            expected: "rover position",
+        })?;
+        let start = self.parse_position(&plateau, 2, start)?;
+
+        Ok((plateau, start))
+    }
+
+    fn no_more_lines(lines: &[&str], count: usize) -> Result<(), RoverParseError> {
+        lines
+            .iter()
+            .enumerate()
+            .skip(count)
+            .try_for_each(|(index, extra)| Tokens::new(index + 1, extra).end())
+    }
+
+    fn parse_goal(
+        &self,
+        plateau: &Plateau,
+        line: usize,
+        goal: &str,
+    ) -> Result<Goal, RoverParseError> {
+        let mut tokens = Tokens::new(line, goal);
+        let x = tokens.coordinate("goal x coordinate")?;
+        let y = tokens.coordinate("goal y coordinate")?;
+        let z = match plateau.altitude {
+            Some(_) => Some(tokens.coordinate("goal altitude")?),
+            None => None,
+        };
+        let heading = tokens.next("goal heading").ok();
+        tokens.end()?;
+
+        let coordinate = Coordinate::new(x, y).with_altitude(z);
+        if !plateau.contains(&coordinate) {
+            return Err(RoverParseError::OutsidePlateau {
+                line,
+                column: Tokens::column_of(goal, goal.trim_start()),
+                token: coordinate.to_string(),
+            });
+        }
+
+        let heading = heading
+            .map(|(column, heading)| {
+                DirectionFactory::create(self.compass, heading).ok_or(
+                    RoverParseError::InvalidDirection {
+                        line,
+                        column,
+                        token: heading.to_string(),
+                    },
+                )
+            })
+            .transpose()?;
+
+        Ok((coordinate, heading))
+    }
+}
+
+struct Ground<'a> {
+    plateau: &'a Plateau,
+    obstacles: &'a ObstacleMap,
+    terrain: &'a TerrainMap,
+}
+
+impl<'a> Ground<'a> {
+    fn new(plateau: &'a Plateau, obstacles: &'a ObstacleMap, terrain: &'a TerrainMap) -> Self {
+        Self {
+            plateau,
+            obstacles,
+            terrain,
+        }
+    }
+
+    fn is_open(&self, coordinate: &Coordinate) -> bool {
+        self.plateau.contains(coordinate)
+            && !self.obstacles.is_blocked(coordinate)
+            && self.terrain.is_passable(coordinate)
+    }
+
+    fn cost(&self, coordinate: &Coordinate) -> usize {
+        self.terrain.cost(coordinate).unwrap_or(0) as usize
+    }
+}
+
+fn route(
+    ground: &Ground,
+    start: &Position,
+    goal: &Coordinate,
+    heading: Option<&dyn Direction>,
+) -> Option<String> {
+    let reached = |position: &Position| {
+        position.coordinate == *goal
+            && heading.is_none_or(|heading| position.direction.to_string() == heading.to_string())
+    };
+    let cheapest = ground.terrain.cheapest() as usize;
+    let estimate = |position: &Position| {
+        let dx = goal.x as i64 - position.coordinate.x as i64;
+        let dy = goal.y as i64 - position.coordinate.y as i64;
+        let dz = goal.z.unwrap_or(0) as i64 - position.coordinate.z.unwrap_or(0) as i64;
+        let distance = dz.abs()
+            + match position.direction.compass() {
+                Compass::FourWay => dx.abs() + dy.abs(),
+                Compass::EightWay => dx.abs().max(dy.abs()),
+                Compass::Hex => dx.abs().max(dy.abs()).max((dx + dy).abs()),
+            };
+
+        distance as usize * cheapest
+    };
+    let mut visited: Vec<(Position, Option<(usize, char)>)> = vec![(start.clone(), None)];
+    let mut costs = HashMap::from([(key(start), 0)]);
+    let mut open = BinaryHeap::from([Reverse((estimate(start), 0, 0))]);
+
+    while let Some(Reverse((_, cost, index))) = open.pop() {
+        let position = visited[index].0.clone();
+
+        if reached(&position) {
+            return Some(commands(&visited, index));
+        }
+        if costs[&key(&position)] < cost {
+            continue;
+        }
+
+        for (command, next, step_cost) in successors(ground, &position) {
+            let next_cost = cost + step_cost;
+            if costs
+                .get(&key(&next))
+                .is_some_and(|known| *known <= next_cost)
+            {
+                continue;
+            }
+
+            costs.insert(key(&next), next_cost);
+            open.push(Reverse((
+                next_cost + estimate(&next),
+                next_cost,
+                visited.len(),
+            )));
+            visited.push((next, Some((index, command))));
+        }
+    }
+
+    None
+}
+
+fn sweep(plateau: &Plateau, altitude: Option<u32>) -> impl Iterator<Item = Coordinate> + '_ {
+    (0..=plateau.height).flat_map(move |y| {
+        (0..=plateau.width).map(move |x| match y % 2 {
+            0 => Coordinate::new(x, y).with_altitude(altitude),
+            _ => Coordinate::new(plateau.width - x, y).with_altitude(altitude),
+        })
+    })
+}
+
+fn reachable(ground: &Ground, start: &Position) -> HashSet<Coordinate> {
+    let mut reachable = HashSet::from([start.coordinate]);
+    let mut frontier = VecDeque::from([start.coordinate]);
+    let level = start.direction.level();
+    let mut directions: Vec<Box<dyn Direction>> = (0..level.compass().points())
+        .scan(level.clone(), |direction, _| {
+            *direction = direction.turn_left();
+            Some(direction.clone())
+        })
+        .collect();
+    if ground.plateau.altitude.is_some() {
+        directions.extend([level.pitch_up(), level.pitch_down()]);
+    }
+
+    while let Some(cell) = frontier.pop_front() {
+        for direction in &directions {
+            let Some(next) = cell
+                .apply_vector(direction.move_vector())
+                .filter(|next| ground.is_open(next))
+            else {
+                continue;
+            };
+
+            if reachable.insert(next) {
+                frontier.push_back(next);
+            }
+        }
+    }
+
+    reachable
+}
+
+fn advance(position: &mut Position, command: char) {
+    match command {
+        'L' => position.turn_left(),
+        'R' => position.turn_right(),
+        'C' => position.pitch_up(),
+        'D' => position.pitch_down(),
+        _ => {
+            if let Some(coordinate) = position.next_coordinate(position.direction.move_vector()) {
+                position.move_to(coordinate);
+            }
+        }
+    }
+}
+
+fn key(position: &Position) -> (Coordinate, String) {
+    (position.coordinate, position.direction.to_string())
+}
+
+fn successors(ground: &Ground, position: &Position) -> Vec<(char, Position, usize)> {
+    let mut left = position.clone();
+    left.turn_left();
+    let mut right = position.clone();
+    right.turn_right();
+    let mut successors = vec![('L', left, 1), ('R', right, 1)];
+
+    if ground.plateau.altitude.is_some() {
+        let mut up = position.clone();
+        up.pitch_up();
+        let mut down = position.clone();
+        down.pitch_down();
+        successors.extend([('C', up, 1), ('D', down, 1)]);
+    }
+
+    if let Some(coordinate) = position
+        .next_coordinate(position.direction.move_vector())
+        .filter(|coordinate| ground.is_open(coordinate))
+    {
+        let mut forward = position.clone();
+        forward.move_to(coordinate);
+        successors.push(('M', forward, ground.cost(&coordinate)));
+    }
+
+    successors
+}
+
+fn commands(visited: &[(Position, Option<(usize, char)>)], mut index: usize) -> String {
+    let mut commands = Vec::new();
+
+    while let Some((parent, command)) = visited[index].1 {
+        commands.push(command);
+        index = parent;
+    }
+
+    commands.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod mars_rover_2_planner_tests {
+    use crate::mars_rover2::test_support::rock_map;
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    #[test_case("5 5\n1 2 N\n1 2", "" ; "already there")]
+    #[test_case("5 5\n1 2 N\n1 4", "MM" ; "straight ahead")]
+    #[test_case("5 5\n1 2 N\n3 3", "MRMM" ; "turn once")]
+    #[test_case("5 5\n1 2 N\n1 0", "LLMM" ; "behind")]
+    #[test_case("5 5\n1 2 N\n1 2 W", "L" ; "turn in place")]
+    #[test_case("5 5\n0 0 N\n2 2 S", "MMRMMR" ; "with goal heading")]
+    fn plans_the_shortest_route(instructions: &str, expected: &str) {
+        let planner = RoutePlanner::new(Parser::new());
+
+        assert_eq!(
+            Ok(Some(String::from(expected))),
+            planner.plan(String::from(instructions))
+        );
+    }
+
+    #[test]
+    fn plans_around_obstacles() {
+        let obstacles: ObstacleMap = "...\n.#.\n...".parse().unwrap();
+        let planner = RoutePlanner::new(Parser::new()).with_obstacles(obstacles.clone());
+        let mut rover = Rover::new(Parser::new()).with_obstacles(obstacles);
+
+        let route = planner
+            .plan(String::from("2 2\n1 0 N\n1 2"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(7, route.len());
+        assert!(rover
+            .execute(format!("2 2\n1 0 N\n{}", route))
+            .to_string()
+            .starts_with("1 2"));
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_route() {
+        let planner = RoutePlanner::new(Parser::new()).with_obstacles(
+            "\
+..#
+.#.
+..."
+            .parse()
+            .unwrap(),
+        );
+
+        assert_eq!(Ok(None), planner.plan(String::from("2 2\n0 0 N\n2 2")));
+    }
+
+    #[test_case("5 5\n1 2 N", RoverParseError::MissingLine { line: 3, expected: "goal coordinate" } ; "missing goal")]
+    #[test_case("5 5\n1 2 N\n6 2", RoverParseError::OutsidePlateau { line: 3, column: 1, token: String::from("6 2") } ; "goal outside plateau")]
+    #[test_case("5 5\n1 2 N\n3 3 Q", RoverParseError::InvalidDirection { line: 3, column: 5, token: String::from("Q") } ; "invalid goal heading")]
+    #[test_case("5 5\n1 2 N\n3 3 N x", RoverParseError::UnexpectedToken { line: 3, column: 7, token: String::from("x") } ; "trailing token")]
+    #[test_case("5 5\n1 2 N\n3 3\nMM", RoverParseError::UnexpectedToken { line: 4, column: 1, token: String::from("MM") } ; "extra line")]
+    fn reports_invalid_route_requests(instructions: &str, expected: RoverParseError) {
+        let planner = RoutePlanner::new(Parser::new());
+
+        assert_eq!(Err(expected), planner.plan(String::from(instructions)));
+    }
+
+    prop_compose! {
+        fn route_request()(width in 0u32..6, height in 0u32..6)(
+            (map, start) in rock_map(width, height, 0.25),
+            heading in 0usize..4,
+            goal in (0..=width, 0..=height, proptest::option::of(0usize..4)),
+            width in Just(width),
+            height in Just(height),
+        ) -> (String, String, String, String) {
+            let headings = ["N", "E", "S", "W"];
+            let goal_heading = goal.2.map_or(String::new(), |heading| format!(" {}", headings[heading]));
+
+            (
+                map,
+                format!("{} {}", width, height),
+                format!("{} {} {}", start.0, start.1, headings[heading]),
+                format!("{} {}{}", goal.0, goal.1, goal_heading),
+            )
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn planned_routes_land_the_rover_on_the_goal(
+            (map, plateau, start, goal) in route_request()
+        ) {
+            let obstacles: ObstacleMap = map.parse().unwrap();
+            let planner = RoutePlanner::new(Parser::new()).with_obstacles(obstacles.clone());
+            let route = planner
+                .plan(format!("{}\n{}\n{}", plateau, start, goal))
+                .unwrap();
+
+            if let Some(route) = route {
+                prop_assert!(route.chars().all(|c| "LRM".contains(c)));
+
+                let mut rover = Rover::new(Parser::new()).with_obstacles(obstacles);
+                let position = rover.execute(format!("{}\n{}\n{}", plateau, start, route)).to_string();
+
+                prop_assert!(
+                    position.starts_with(&goal),
+                    "{} does not reach {}",
+                    position,
+                    goal
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_coverage_tests {
+    use crate::mars_rover2::test_support::rock_map;
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn sweeps_the_plateau_row_by_row() {
+        let planner = CoveragePlanner::new(Parser::new());
+
+        let coverage = planner.plan(String::from("2 1\n0 0 E")).unwrap();
+
+        assert_eq!("MMLMLMM", coverage.commands());
+        assert_eq!(Vec::<(u32, u32)>::new(), coverage.unreachable());
+    }
+
+    #[test]
+    fn routes_around_obstacles() {
+        let obstacles: ObstacleMap = "...\n.#.\n...".parse().unwrap();
+        let planner = CoveragePlanner::new(Parser::new()).with_obstacles(obstacles.clone());
+        let mut rover = Rover::new(Parser::new()).with_obstacles(obstacles);
+
+        let coverage = planner.plan(String::from("2 2\n0 0 E")).unwrap();
+        let trace = rover
+            .try_trace(format!("2 2\n0 0 E\n{}", coverage.commands()))
+            .unwrap();
+        let visited: HashSet<_> = trace
+            .positions()
+            .map(|position| position.coordinate)
+            .collect();
+
+        assert_eq!(8, visited.len());
+        assert!(!visited.contains(&Coordinate::new(1, 1)));
+    }
+
+    #[test]
+    fn reports_the_unreachable_cells() {
+        let planner = CoveragePlanner::new(Parser::new()).with_obstacles(
+            "\
+.#.
+#..
+..."
+            .parse()
+            .unwrap(),
+        );
+
+        let coverage = planner.plan(String::from("2 2\n2 0 N")).unwrap();
+
+        assert_eq!(vec![(0, 2)], coverage.unreachable());
+    }
+
+    #[test]
+    fn rejects_anything_after_the_rover_position() {
+        let planner = CoveragePlanner::new(Parser::new());
+
+        assert_eq!(
+            Err(RoverParseError::UnexpectedToken {
+                line: 3,
+                column: 1,
+                token: String::from("MM")
+            }),
+            planner.plan(String::from("2 2\n0 0 N\nMM"))
+        );
+    }
+
+    prop_compose! {
+        fn survey()(width in 0u32..6, height in 0u32..6)(
+            (map, start) in rock_map(width, height, 0.25),
+            heading in proptest::sample::select(vec!["N", "E", "S", "W"]),
+            width in Just(width),
+            height in Just(height),
+        ) -> (String, String) {
+            (map, format!("{} {}\n{} {} {}", width, height, start.0, start.1, heading))
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn every_free_cell_is_either_visited_or_reported(
+            (map, deployment) in survey()
+        ) {
+            let obstacles: ObstacleMap = map.parse().unwrap();
+            let planner = CoveragePlanner::new(Parser::new()).with_obstacles(obstacles.clone());
+            let mut rover = Rover::new(Parser::new()).with_obstacles(obstacles.clone());
+
+            let coverage = planner.plan(deployment.clone()).unwrap();
+            let trace = rover
+                .try_trace(format!("{}\n{}", deployment, coverage.commands()))
+                .unwrap();
+            let visited: HashSet<_> = trace
+                .positions()
+                .map(|position| (position.coordinate.x, position.coordinate.y))
+                .collect();
+            let unreachable: HashSet<_> = coverage.unreachable().into_iter().collect();
+
+            let plateau = rover.plateau.unwrap();
+            for y in 0..=plateau.height {
+                for x in 0..=plateau.width {
+                    let cell = (x, y);
+                    let free = !obstacles.is_blocked(&Coordinate::new(x, y))
+                        || trace.start.coordinate == Coordinate::new(x, y);
+                    prop_assert_eq!(free, visited.contains(&cell) || unreachable.contains(&cell));
+                    prop_assert!(!(visited.contains(&cell) && unreachable.contains(&cell)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod mars_rover_2_return_to_base_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    #[test_case("5 5\n1 2 N\nLMLMLMLMM", "BBRBRBRBR" ; "loop")]
+    #[test_case("5 5\n3 3 E\nMMRMMRMRRM", "BLLBLBBLBB" ; "zig zag")]
+    #[test_case("5 5\n1 2 N\nUBHM", "BMU" ; "u turn and hold")]
+    #[test_case("5 5\n1 5 N\nMMRM", "BL" ; "rejected moves")]
+    #[test_case("5 5\n1 2 N\n", "" ; "no commands")]
+    fn retraces_the_recorded_path(instructions: &str, expected: &str) {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from(instructions));
+
+        assert_eq!(
+            Some(String::from(expected)),
+            rover.return_to_base(ReturnStrategy::Retrace)
+        );
+    }
+
+    #[test]
+    fn retraces_across_a_wrapped_edge() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Wrap);
+
+        let end = rover.execute(String::from("5 5\n1 5 N\nMM")).to_string();
+        let back = rover.return_to_base(ReturnStrategy::Retrace).unwrap();
+
+        assert_eq!("BB", back);
+        assert_eq!(
+            "1 5 N",
+            Rover::new(Parser::new())
+                .with_edge_policy(EdgePolicy::Wrap)
+                .execute(format!("5 5\n{}\n{}", end, back))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn plans_the_shortest_way_back() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("5 5\n1 2 N\nLMLMLMLMM"));
+
+        assert_eq!(
+            Some(String::from("LLMLL")),
+            rover.return_to_base(ReturnStrategy::Shortest)
+        );
+    }
+
+    #[test]
+    fn shortest_way_back_avoids_obstacles() {
+        let obstacles: ObstacleMap = "...\n.#.\n...".parse().unwrap();
+        let mut rover = Rover::new(Parser::new()).with_obstacles(obstacles.clone());
+
+        let end = rover
+            .execute(String::from("2 2\n1 0 N\nRMLMMLM"))
+            .to_string();
+        let back = rover.return_to_base(ReturnStrategy::Shortest).unwrap();
+
+        assert_eq!("1 2 W", end);
+        assert_eq!(
+            "1 0 N",
+            Rover::new(Parser::new())
+                .with_obstacles(obstacles)
+                .execute(format!("2 2\n{}\n{}", end, back))
+                .to_string()
+        );
+    }
+
+    prop_compose! {
+        fn traverse()(width in 0u32..5, height in 0u32..5)(
+            start in (0..=width, 0..=height, proptest::sample::select(vec!["N", "E", "S", "W"])),
+            commands in "[LRMBUH]{0,30}",
+            width in Just(width),
+            height in Just(height),
+        ) -> (String, String) {
+            (format!("{} {}", width, height), format!("{} {} {}\n{}", start.0, start.1, start.2, commands))
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn both_strategies_bring_the_rover_back_to_its_start(
+            (plateau, rover_input) in traverse(),
+            strategy in proptest::sample::select(vec![ReturnStrategy::Retrace, ReturnStrategy::Shortest]),
+        ) {
+            let mut rover = Rover::new(Parser::new());
+            let start = rover_input.lines().next().unwrap().to_string();
+
+            let end = rover.execute(format!("{}\n{}", plateau, rover_input)).to_string();
+            let back = rover.return_to_base(strategy).unwrap();
+
+            prop_assert_eq!(
+                start,
+                Rover::new(Parser::new())
+                    .execute(format!("{}\n{}\n{}", plateau, end, back))
+                    .to_string()
+            );
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use super::{Compass, Coordinate, Mission, ObstacleMap, Plateau, RenderError, Rover, Trace};
+
+const FREE: char = '.';
+const OBSTACLE: char = '#';
+const VISITED: char = '*';
+
+impl Rover {
+    pub fn render(&self) -> String {
+        self.try_render().unwrap_or_else(|error| error.to_string())
+    }
+
+    pub fn try_render(&self) -> Result<String, RenderError> {
+        match &self.plateau {
+            Some(plateau) => render(
+                self.parser.compass,
+                plateau,
+                &self.obstacles,
+                std::slice::from_ref(&self.trace),
+            ),
+            None => Ok(String::new()),
+        }
+    }
+}
+
+impl Mission {
+    pub fn render(&self) -> String {
+        self.try_render().unwrap_or_else(|error| error.to_string())
+    }
+
+    pub fn try_render(&self) -> Result<String, RenderError> {
+        match &self.plateau {
+            Some(plateau) => render(self.parser.compass, plateau, &self.obstacles, &self.traces),
+            None => Ok(String::new()),
+        }
+    }
+}
+
+fn render(
+    compass: Compass,
+    plateau: &Plateau,
+    obstacles: &ObstacleMap,
+    traces: &[Trace],
+) -> Result<String, RenderError> {
+    plateau.renderable()?;
+
+    let columns = plateau.width as usize + 1;
+    let rows = plateau.height as usize + 1;
+    let mut grid = vec![vec![FREE; columns]; rows];
+    let mut draw = |coordinate: &Coordinate, glyph: char| {
+        if plateau.contains(coordinate) {
+            grid[plateau.height as usize - coordinate.y as usize][coordinate.x as usize] = glyph;
+        }
+    };
+
+    obstacles
+        .obstacles
+        .iter()
+        .for_each(|obstacle| draw(obstacle, OBSTACLE));
+    traces
+        .iter()
+        .flat_map(Trace::positions)
+        .for_each(|position| draw(&position.coordinate, VISITED));
+    traces.iter().map(Trace::last).for_each(|position| {
+        draw(&position.coordinate, position.direction.arrow());
+    });
+
+    Ok(match compass {
+        Compass::Hex => grid
+            .iter()
+            .enumerate()
+            .map(|(row, cells)| {
+                let indent = " ".repeat(plateau.height as usize - row);
+                let cells: Vec<String> = cells.iter().map(char::to_string).collect();
+
+                indent + &cells.join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => grid
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    })
+}
+
+#[cfg(test)]
+mod mars_rover_2_render_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test]
+    fn draws_the_plateau_from_the_first_input_line() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("3 1\n0 0 E"));
+
+        assert_eq!("....\n>...", rover.render());
+    }
+
+    #[test_case("N", '^' ; "north")]
+    #[test_case("E", '>' ; "east")]
+    #[test_case("S", 'v' ; "south")]
+    #[test_case("W", '<' ; "west")]
+    fn draws_the_rover_as_an_arrow_for_its_direction(direction: &str, arrow: char) {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(format!("0 0\n0 0 {}", direction));
+
+        assert_eq!(arrow.to_string(), rover.render());
+    }
+
+    #[test]
+    fn marks_the_visited_cells() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("4 2\n0 0 N\nMMRMM"));
+
+        assert_eq!("**>..\n*....\n*....", rover.render());
+    }
+
+    #[test]
+    fn draws_the_obstacles() {
+        let mut rover = Rover::new(Parser::new()).with_obstacles("..#\n...\n#..".parse().unwrap());
+
+        rover.execute(String::from("2 2\n0 1 E\nMMLM"));
+
+        assert_eq!("..#\n**^\n#..", rover.render());
+    }
+
+    #[test]
+    fn draws_every_rover_of_a_mission() {
+        let mut mission = Mission::new(Parser::new()).with_obstacles(
+            "\
+......
+..#...
+......
+......
+......
+......"
+                .parse()
+                .unwrap(),
+        );
+
+        mission.execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMMRMMRMRRM"));
+
+        assert_eq!(
+            "\
+......
+..#...
+.^.***
+**...*
+**..*>
+......",
+            mission.render()
+        );
+    }
+
+    #[test]
+    fn blocked_rover_is_drawn_at_its_last_safe_position() {
+        let mut mission = Mission::new(Parser::new()).with_obstacles(".#\n..".parse().unwrap());
+
+        mission.execute(String::from("1 1\n1 0 N\nMM"));
+
+        assert_eq!(".#\n.^", mission.render());
+    }
+
+    #[test]
+    fn rover_renders_nothing_before_it_runs() {
+        let rover = Rover::new(Parser::new());
+
+        assert_eq!("", rover.render());
+    }
+
+    #[test]
+    fn refuses_to_render_a_plateau_above_the_size_limit() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("100000 100000\n1 1 N\nM"));
+
+        assert_eq!(
+            Err(RenderError::PlateauTooLarge {
+                columns: 100_001,
+                rows: 100_001,
+                limit: 1_000_000
+            }),
+            rover.try_render()
+        );
+        assert_eq!(
+            "plateau of 100001 x 100001 cells is larger than the 1000000 cells that can be rendered",
+            rover.render()
+        );
+    }
+
+    #[test]
+    fn refuses_to_render_the_largest_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("4294967295 4294967295\n1 1 N\nM"));
+
+        assert_eq!(
+            Err(RenderError::PlateauTooLarge {
+                columns: 4_294_967_296,
+                rows: 4_294_967_296,
+                limit: 1_000_000
+            }),
+            rover.try_render()
+        );
+    }
+
+    #[test]
+    fn mission_renders_nothing_before_it_runs() {
+        let mission = Mission::new(Parser::new());
+
+        assert_eq!("", mission.render());
+    }
+}
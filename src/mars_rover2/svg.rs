@@ -0,0 +1,317 @@
+use std::fmt::Write;
+use std::path::Path;
+use std::{fs, io};
+
+use super::{Coordinate, Mission, ObstacleMap, Plateau, Position, RenderError, Rover, Trace};
+
+const CELL: i64 = 40;
+const GRID: &str = "#cccccc";
+const OBSTACLE: &str = "#555555";
+const COLOURS: [&str; 6] = [
+    "#d62728", "#1f77b4", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b",
+];
+
+impl Rover {
+    pub fn svg(&self) -> String {
+        self.try_svg().unwrap_or_else(|error| error.to_string())
+    }
+
+    pub fn try_svg(&self) -> Result<String, RenderError> {
+        match &self.plateau {
+            Some(plateau) => svg(plateau, &self.obstacles, std::slice::from_ref(&self.trace)),
+            None => Ok(String::new()),
+        }
+    }
+}
+
+impl Mission {
+    pub fn svg(&self) -> String {
+        self.try_svg().unwrap_or_else(|error| error.to_string())
+    }
+
+    pub fn try_svg(&self) -> Result<String, RenderError> {
+        match &self.plateau {
+            Some(plateau) => svg(plateau, &self.obstacles, &self.traces),
+            None => Ok(String::new()),
+        }
+    }
+
+    pub fn export_svg(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let svg = self
+            .try_svg()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+        fs::write(path, svg)
+    }
+}
+
+fn svg(
+    plateau: &Plateau,
+    obstacles: &ObstacleMap,
+    traces: &[Trace],
+) -> Result<String, RenderError> {
+    plateau.renderable()?;
+
+    let columns = plateau.width as i64 + 1;
+    let rows = plateau.height as i64 + 1;
+    let (width, height) = (columns * CELL, rows * CELL);
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{width}" height="{height}" fill="white"/>"#
+    );
+
+    for column in 0..=columns {
+        let x = column * CELL;
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{x}" y1="0" x2="{x}" y2="{height}" stroke="{GRID}"/>"#
+        );
+    }
+    for row in 0..=rows {
+        let y = row * CELL;
+        let _ = writeln!(
+            svg,
+            r#"<line x1="0" y1="{y}" x2="{width}" y2="{y}" stroke="{GRID}"/>"#
+        );
+    }
+
+    let mut rocks: Vec<_> = obstacles
+        .obstacles
+        .iter()
+        .filter(|obstacle| plateau.contains(obstacle))
+        .collect();
+    rocks.sort_by_key(|obstacle| (obstacle.x, obstacle.y));
+    for rock in rocks {
+        let (x, y) = corner(plateau, rock);
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x}" y="{y}" width="{CELL}" height="{CELL}" fill="{OBSTACLE}"/>"#
+        );
+    }
+
+    for (index, trace) in traces.iter().enumerate() {
+        traverse(&mut svg, plateau, trace, COLOURS[index % COLOURS.len()]);
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+fn traverse(svg: &mut String, plateau: &Plateau, trace: &Trace, colour: &str) {
+    for segment in segments(trace) {
+        let points = segment
+            .iter()
+            .map(|coordinate| {
+                let (x, y) = centre(plateau, coordinate);
+                format!("{x},{y}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{points}" fill="none" stroke="{colour}" stroke-width="3"/>"#
+        );
+    }
+
+    let (x, y) = centre(plateau, &trace.start.coordinate);
+    let _ = writeln!(svg, r#"<circle cx="{x}" cy="{y}" r="6" fill="{colour}"/>"#);
+    heading(svg, plateau, &trace.start, colour);
+
+    let end = trace.last();
+    let (x, y) = centre(plateau, &end.coordinate);
+    let _ = writeln!(
+        svg,
+        r#"<circle cx="{x}" cy="{y}" r="12" fill="none" stroke="{colour}" stroke-width="2"/>"#
+    );
+    heading(svg, plateau, end, colour);
+}
+
+fn segments(trace: &Trace) -> Vec<Vec<Coordinate>> {
+    let mut segments: Vec<Vec<Coordinate>> = Vec::new();
+
+    for position in trace.positions() {
+        let coordinate = position.coordinate;
+        match segments.last_mut() {
+            Some(segment) if segment.last() == Some(&coordinate) => {}
+            Some(segment) if adjacent(segment[segment.len() - 1], coordinate) => {
+                segment.push(coordinate)
+            }
+            _ => segments.push(vec![coordinate]),
+        }
+    }
+
+    segments
+}
+
+fn adjacent(from: Coordinate, to: Coordinate) -> bool {
+    from.x.abs_diff(to.x) <= 1 && from.y.abs_diff(to.y) <= 1
+}
+
+fn heading(svg: &mut String, plateau: &Plateau, position: &Position, colour: &str) {
+    let (x, y) = centre(plateau, &position.coordinate);
+    let vector = position.direction.move_vector();
+    let (dx, dy) = (vector.0 as i64, -(vector.1 as i64));
+    let tip = (x + dx * 16, y + dy * 16);
+    let left = (x + dx * 6 - dy * 7, y + dy * 6 + dx * 7);
+    let right = (x + dx * 6 + dy * 7, y + dy * 6 - dx * 7);
+    let _ = writeln!(
+        svg,
+        r#"<polygon points="{},{} {},{} {},{}" fill="{colour}"/>"#,
+        tip.0, tip.1, left.0, left.1, right.0, right.1
+    );
+}
+
+fn corner(plateau: &Plateau, coordinate: &Coordinate) -> (i64, i64) {
+    (
+        coordinate.x as i64 * CELL,
+        (plateau.height as i64 - coordinate.y as i64) * CELL,
+    )
+}
+
+fn centre(plateau: &Plateau, coordinate: &Coordinate) -> (i64, i64) {
+    let (x, y) = corner(plateau, coordinate);
+    (x + CELL / 2, y + CELL / 2)
+}
+
+#[cfg(test)]
+mod mars_rover_2_svg_tests {
+    use crate::mars_rover2::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn exports_an_empty_plateau_as_a_grid() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("0 0\n0 0 N"));
+
+        assert_eq!(
+            "\
+<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"40\" height=\"40\" viewBox=\"0 0 40 40\">
+<rect width=\"40\" height=\"40\" fill=\"white\"/>
+<line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"40\" stroke=\"#cccccc\"/>
+<line x1=\"40\" y1=\"0\" x2=\"40\" y2=\"40\" stroke=\"#cccccc\"/>
+<line x1=\"0\" y1=\"0\" x2=\"40\" y2=\"0\" stroke=\"#cccccc\"/>
+<line x1=\"0\" y1=\"40\" x2=\"40\" y2=\"40\" stroke=\"#cccccc\"/>
+<polyline points=\"20,20\" fill=\"none\" stroke=\"#d62728\" stroke-width=\"3\"/>
+<circle cx=\"20\" cy=\"20\" r=\"6\" fill=\"#d62728\"/>
+<polygon points=\"20,4 27,14 13,14\" fill=\"#d62728\"/>
+<circle cx=\"20\" cy=\"20\" r=\"12\" fill=\"none\" stroke=\"#d62728\" stroke-width=\"2\"/>
+<polygon points=\"20,4 27,14 13,14\" fill=\"#d62728\"/>
+</svg>
+",
+            rover.svg()
+        );
+    }
+
+    #[test]
+    fn draws_the_path_as_a_polyline_from_the_top_left() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("2 2\n0 0 N\nMMRM"));
+
+        assert!(rover
+            .svg()
+            .contains(r##"<polyline points="20,100 20,60 20,20 60,20" fill="none""##));
+    }
+
+    #[test]
+    fn heading_arrow_points_in_the_final_direction() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("1 0\n0 0 N\nR"));
+
+        assert!(rover
+            .svg()
+            .contains(r##"<polygon points="36,20 26,27 26,13" fill="#d62728"/>"##));
+    }
+
+    #[test]
+    fn draws_the_obstacles() {
+        let mut rover = Rover::new(Parser::new()).with_obstacles("#.\n.#".parse().unwrap());
+
+        rover.execute(String::from("1 1\n0 0 N"));
+
+        let svg = rover.svg();
+        assert!(svg.contains(r##"<rect x="0" y="0" width="40" height="40" fill="#555555"/>"##));
+        assert!(svg.contains(r##"<rect x="40" y="40" width="40" height="40" fill="#555555"/>"##));
+    }
+
+    #[test]
+    fn wrapping_splits_the_path() {
+        let mut rover = Rover::new(Parser::new()).with_edge_policy(EdgePolicy::Wrap);
+
+        rover.execute(String::from("0 2\n0 1 N\nMM"));
+
+        let svg = rover.svg();
+        assert!(svg.contains(r##"<polyline points="20,60 20,20" "##));
+        assert!(svg.contains(r##"<polyline points="20,100" "##));
+    }
+
+    #[test]
+    fn each_rover_of_a_mission_gets_its_own_colour() {
+        let mut mission = Mission::new(Parser::new());
+
+        mission.execute(String::from("5 5\n1 2 N\nLMLMLMLMM\n3 3 E\nMMRMMRMRRM"));
+
+        let svg = mission.svg();
+        assert!(svg.contains(r##"stroke="#d62728" stroke-width="3""##));
+        assert!(svg.contains(r##"stroke="#1f77b4" stroke-width="3""##));
+    }
+
+    #[test]
+    fn refuses_to_export_a_plateau_above_the_size_limit() {
+        let mut mission = Mission::new(Parser::new());
+
+        mission.execute(String::from("4294967295 1\n1 1 N\nM"));
+
+        assert_eq!(
+            Err(RenderError::PlateauTooLarge {
+                columns: 4_294_967_296,
+                rows: 2,
+                limit: 1_000_000
+            }),
+            mission.try_svg()
+        );
+    }
+
+    #[test]
+    fn refuses_to_export_the_largest_plateau() {
+        let mut rover = Rover::new(Parser::new());
+
+        rover.execute(String::from("4294967295 4294967295\n1 1 N\nM"));
+
+        assert_eq!(
+            Err(RenderError::PlateauTooLarge {
+                columns: 4_294_967_296,
+                rows: 4_294_967_296,
+                limit: 1_000_000
+            }),
+            rover.try_svg()
+        );
+    }
+
+    #[test]
+    fn rover_exports_nothing_before_it_runs() {
+        let rover = Rover::new(Parser::new());
+
+        assert_eq!("", rover.svg());
+    }
+
+    #[test]
+    fn mission_exports_the_svg_to_a_file() {
+        let mut mission = Mission::new(Parser::new());
+        let path = std::env::temp_dir().join("mars_rover_2_mission.svg");
+
+        mission.execute(String::from("5 5\n1 2 N\nLMLMLMLMM"));
+        mission.export_svg(&path).unwrap();
+
+        assert_eq!(mission.svg(), std::fs::read_to_string(&path).unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+}
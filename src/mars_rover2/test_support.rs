@@ -0,0 +1,30 @@
+use proptest::prelude::*;
+
+pub fn rock_map(
+    width: u32,
+    height: u32,
+    density: f64,
+) -> impl Strategy<Value = (String, (u32, u32))> {
+    let columns = width as usize + 1;
+    let cells = columns * (height as usize + 1);
+
+    (
+        proptest::collection::vec(proptest::bool::weighted(density), cells),
+        0..=width,
+        0..=height,
+    )
+        .prop_map(move |(mut rocks, x, y)| {
+            rocks[(height - y) as usize * columns + x as usize] = false;
+            let map = rocks
+                .chunks(columns)
+                .map(|row| {
+                    row.iter()
+                        .map(|rock| if *rock { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            (map, (x, y))
+        })
+}
@@ -0,0 +1,131 @@
+use crate::{mars_rover, mars_rover2};
+
+pub trait MarsRover {
+    type Error;
+
+    fn execute(&mut self, instructions: String) -> String;
+    fn try_execute(&mut self, instructions: String) -> Result<String, Self::Error>;
+}
+
+impl MarsRover for mars_rover::Rover {
+    type Error = mars_rover::RoverParseError;
+
+    fn execute(&mut self, instructions: String) -> String {
+        mars_rover::Rover::execute(self, instructions).to_string()
+    }
+
+    fn try_execute(&mut self, instructions: String) -> Result<String, Self::Error> {
+        mars_rover::Rover::try_execute(self, instructions).map(|report| report.to_string())
+    }
+}
+
+impl MarsRover for mars_rover2::Rover {
+    type Error = mars_rover2::RoverParseError;
+
+    fn execute(&mut self, instructions: String) -> String {
+        mars_rover2::Rover::execute(self, instructions).to_string()
+    }
+
+    fn try_execute(&mut self, instructions: String) -> Result<String, Self::Error> {
+        mars_rover2::Rover::try_execute(self, instructions).map(|report| report.to_string())
+    }
+}
+
+#[cfg(test)]
+mod rover_tests {
+    use crate::rover::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    fn drive(rover: &mut impl MarsRover, instructions: &str) -> String {
+        rover.execute(String::from(instructions))
+    }
+
+    fn try_drive<R: MarsRover>(rover: &mut R, instructions: &str) -> Result<String, String>
+    where
+        R::Error: std::fmt::Display,
+    {
+        rover
+            .try_execute(String::from(instructions))
+            .map_err(|error| error.to_string())
+    }
+
+    #[test_case("5 5\n1 2 N\nLMLMLMLMM", "1 3 N" ; "first rover")]
+    #[test_case("5 5\n3 3 E\nMMRMMRMRRM", "5 1 E" ; "second rover")]
+    #[test_case("5 5\n0 0 S\nM", "0 0 S" ; "rejected move")]
+    fn both_rovers_drive_through_the_shared_trait(instructions: &str, expected: &str) {
+        let mut first = mars_rover::Rover::new(mars_rover::Parser::new());
+        let mut second = mars_rover2::Rover::new(mars_rover2::Parser::new());
+
+        assert_eq!(expected, drive(&mut first, instructions));
+        assert_eq!(expected, drive(&mut second, instructions));
+    }
+
+    prop_compose! {
+        fn instructions()(width in 0u32..8, height in 0u32..8)(
+            start in (0..=width, 0..=height, proptest::sample::select(vec!["N", "E", "S", "W"])),
+            commands in "[LRM]{0,40}",
+            width in Just(width),
+            height in Just(height),
+        ) -> String {
+            format!("{} {}\n{} {} {}\n{}", width, height, start.0, start.1, start.2, commands)
+        }
+    }
+
+    fn coordinate(limit: u32) -> impl Strategy<Value = String> {
+        prop_oneof![
+            4 => (0..=limit + 2).prop_map(|coordinate| coordinate.to_string()),
+            1 => proptest::sample::select(vec!["", "a", "-1", "4294967296"]).prop_map(String::from),
+        ]
+    }
+
+    prop_compose! {
+        fn malformed_instructions()(width in 0u32..8, height in 0u32..8)(
+            plateau in (coordinate(width), coordinate(height)),
+            start in (coordinate(width), coordinate(height), proptest::sample::select(vec!["N", "E", "S", "W", "X", ""])),
+            extra in proptest::sample::select(vec!["", " N", " X"]),
+            commands in "[LRMX?]{0,20}",
+            lines in 0usize..=3,
+        ) -> String {
+            [
+                format!("{} {}", plateau.0, plateau.1),
+                format!("{} {} {}{}", start.0, start.1, start.2, extra),
+                commands,
+            ][..lines]
+                .join("\n")
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn both_rovers_agree_on_every_program(
+            instructions in instructions(),
+            edge_policy in proptest::sample::select(vec![
+                (mars_rover::EdgePolicy::Stop, mars_rover2::EdgePolicy::Stop),
+                (mars_rover::EdgePolicy::Reject, mars_rover2::EdgePolicy::Reject),
+                (mars_rover::EdgePolicy::Lost, mars_rover2::EdgePolicy::Lost),
+            ]),
+        ) {
+            let mut first = mars_rover::Rover::new(mars_rover::Parser::new()).with_edge_policy(edge_policy.0);
+            let mut second = mars_rover2::Rover::new(mars_rover2::Parser::new()).with_edge_policy(edge_policy.1);
+
+            assert_eq!(drive(&mut first, &instructions), drive(&mut second, &instructions));
+        }
+
+        #[test]
+        fn both_rovers_agree_on_malformed_programs(
+            instructions in malformed_instructions(),
+            edge_policy in proptest::sample::select(vec![
+                (mars_rover::EdgePolicy::Stop, mars_rover2::EdgePolicy::Stop),
+                (mars_rover::EdgePolicy::Reject, mars_rover2::EdgePolicy::Reject),
+                (mars_rover::EdgePolicy::Lost, mars_rover2::EdgePolicy::Lost),
+            ]),
+        ) {
+            let mut first = mars_rover::Rover::new(mars_rover::Parser::new()).with_edge_policy(edge_policy.0);
+            let mut second = mars_rover2::Rover::new(mars_rover2::Parser::new()).with_edge_policy(edge_policy.1);
+
+            assert_eq!(try_drive(&mut first, &instructions), try_drive(&mut second, &instructions));
+        }
+    }
+}